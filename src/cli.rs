@@ -23,6 +23,11 @@ pub struct Cli {
     #[arg(long, global = true, env = "MAHARAJAH_EMBED_MODEL")]
     pub embed_model: Option<String>,
 
+    /// Vector store backend address (e.g. `lance:///path/to/db` or
+    /// `memory://` for an ephemeral, non-persisted index)
+    #[arg(long, global = true, env = "MAHARAJAH_DB_ADDR", value_name = "ADDR")]
+    pub db_addr: Option<String>,
+
     /// Target project directory (default: current working directory)
     #[arg(short = 'D', long = "dir", global = true, value_name = "DIR")]
     pub target_dir: Option<PathBuf>,
@@ -49,10 +54,39 @@ pub enum Commands {
     /// Manage the vector database (stats, clear)
     Db(DbArgs),
 
+    /// Re-embed every indexed chunk with the currently configured embedder
+    /// and replace the table with it: the old table is dropped, then recreated
+    /// from the re-embedded rows, so the replacement is not atomic. Use this after switching
+    /// `embed.provider` (or otherwise changing `db.embedding_dim`) so stored
+    /// vectors match the new model without re-indexing source files.
+    Migrate(MigrateArgs),
+
+    /// Watch the target directory and keep the index fresh in the
+    /// background, debouncing bursts of filesystem events. Runs until
+    /// interrupted; while it's running, `find`/`query` skip their own
+    /// auto-refresh and rely on the watcher instead.
+    Watch,
+
+    /// Run the HTTP server (find/query/batch endpoints plus Prometheus
+    /// metrics), watching the target directory and keeping the index fresh
+    /// in the background for the lifetime of the process.
+    Server(ServerArgs),
+
     /// Print the resolved configuration as JSON and exit
     Config,
 }
 
+#[derive(Args, Debug)]
+pub struct ServerArgs {
+    /// Address to bind the HTTP server to
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+
+    /// Port to bind the HTTP server to
+    #[arg(long, default_value_t = 8080)]
+    pub port: u16,
+}
+
 #[derive(Args, Debug)]
 pub struct IndexArgs {
     /// File glob patterns to include (repeatable, e.g. --include '**/*.rs')
@@ -70,6 +104,17 @@ pub struct IndexArgs {
     /// Wipe and rebuild the index from scratch
     #[arg(long)]
     pub reindex: bool,
+
+    /// Force a rebuild of the ANN index on `vector`/`summary_vector`, even if
+    /// the row count is below `db.ann_index_threshold`
+    #[arg(long)]
+    pub optimize: bool,
+
+    /// Don't honor `.gitignore`/`.ignore`/global gitignore rules — walk
+    /// every file not hidden or covered by `--exclude`, same as before this
+    /// crate respected VCS ignore rules
+    #[arg(long)]
+    pub no_ignore: bool,
 }
 
 #[derive(Args, Debug)]
@@ -88,6 +133,37 @@ pub struct QueryArgs {
     /// Print retrieved context chunks before the answer
     #[arg(long)]
     pub show_context: bool,
+
+    /// Retrieval mode: pure vector similarity, pure lexical (BM25 keyword)
+    /// match, all three of content vector + summary vector + lexical fused
+    /// with reciprocal rank fusion, or code+summary vectors blended by
+    /// `--alpha`
+    #[arg(long, value_enum, default_value_t = SearchMode::Vector)]
+    pub mode: SearchMode,
+
+    /// Weight given to code-vector similarity vs. summary-vector similarity
+    /// in `--mode blended` (1.0 = code only, 0.0 = summary only)
+    #[arg(long, default_value_t = 0.7)]
+    pub alpha: f32,
+
+    /// Maximal Marginal Relevance tradeoff applied to the vector-search leg
+    /// of retrieval: 1.0 (the default) reproduces plain top-k-by-score
+    /// ranking; lower values trade relevance for diversity by penalizing
+    /// candidates similar to ones already selected.
+    #[arg(long, default_value_t = 1.0)]
+    pub mmr_lambda: f32,
+
+    /// Only consider chunks from this language (e.g. `rust`)
+    #[arg(long, value_name = "LANG")]
+    pub lang: Option<String>,
+
+    /// Only consider chunks whose file path matches this glob (e.g. `src/db/**`)
+    #[arg(long, value_name = "GLOB")]
+    pub path: Option<String>,
+
+    /// Only consider chunks whose qualified symbol starts with this prefix (e.g. `Store::`)
+    #[arg(long, value_name = "PREFIX")]
+    pub symbol_prefix: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -95,13 +171,79 @@ pub struct FindArgs {
     /// Natural language query to search for
     pub prompt: String,
 
-    /// Maximum number of results to show
-    #[arg(short = 'n', long, default_value_t = 10)]
-    pub limit: usize,
+    /// Maximum number of results to show. Falls back to
+    /// `config.retrieve.result_limit` when not passed.
+    #[arg(short = 'n', long)]
+    pub limit: Option<usize>,
 
     /// Output format
     #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
     pub format: OutputFormat,
+
+    /// Retrieval mode: pure vector similarity, pure lexical (BM25 keyword)
+    /// match, all three of content vector + summary vector + lexical fused
+    /// with reciprocal rank fusion, or code+summary vectors blended by
+    /// `--alpha`
+    #[arg(long, value_enum, default_value_t = SearchMode::Vector)]
+    pub mode: SearchMode,
+
+    /// Weight given to code-vector similarity vs. summary-vector similarity
+    /// in `--mode blended` (1.0 = code only, 0.0 = summary only)
+    #[arg(long, default_value_t = 0.7)]
+    pub alpha: f32,
+
+    /// Maximal Marginal Relevance tradeoff applied to the vector-search leg
+    /// of retrieval: 1.0 (the default) reproduces plain top-k-by-score
+    /// ranking; lower values trade relevance for diversity by penalizing
+    /// candidates similar to ones already selected.
+    #[arg(long, default_value_t = 1.0)]
+    pub mmr_lambda: f32,
+
+    /// Only consider chunks from this language (e.g. `rust`)
+    #[arg(long, value_name = "LANG")]
+    pub lang: Option<String>,
+
+    /// Only consider chunks whose file path matches this glob (e.g. `src/db/**`)
+    #[arg(long, value_name = "GLOB")]
+    pub path: Option<String>,
+
+    /// Only consider chunks whose qualified symbol starts with this prefix (e.g. `Store::`)
+    #[arg(long, value_name = "PREFIX")]
+    pub symbol_prefix: Option<String>,
+
+    /// Score cutoff: in `lexical`/`hybrid` mode results scoring below it are
+    /// dropped; in `vector`/`blended` mode, where the score is a distance,
+    /// results above it are dropped. `0.0` keeps every result. Falls back to
+    /// `config.retrieve.min_score` when not passed.
+    #[arg(long)]
+    pub min_score: Option<f32>,
+
+    /// Exclude chunks from this language, regardless of --lang (repeatable).
+    /// Added to `config.retrieve.exclude_languages` when passed.
+    #[arg(long = "exclude-lang", value_name = "LANG")]
+    pub exclude_lang: Vec<String>,
+
+    /// Score bonus added per query term found in a result's symbol, so
+    /// exact-name hits outrank chunks that only match on content/summary.
+    /// Falls back to `config.retrieve.symbol_boost` when not passed.
+    #[arg(long)]
+    pub symbol_boost: Option<f32>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    Vector,
+    Lexical,
+    Hybrid,
+    /// Code vector and summary vector similarity, combined with `alpha`
+    Blended,
+}
+
+#[derive(Args, Debug)]
+pub struct MigrateArgs {
+    /// Confirm the migration (drops and rebuilds the configured table)
+    #[arg(long)]
+    pub yes: bool,
 }
 
 #[derive(Args, Debug)]
@@ -119,6 +261,16 @@ pub enum DbAction {
         #[arg(long)]
         yes: bool,
     },
+    /// Show embedding cache statistics (entry count)
+    CacheStats,
+    /// Prune the embedding cache down to `config.cache.max_entries`
+    CachePrune,
+    /// Show callers and callees of a symbol, resolved from every indexed
+    /// chunk's extracted references (see `indexer::graph::CallGraph`)
+    Graph {
+        /// Qualified symbol to inspect (e.g. `Store::insert`)
+        symbol: String,
+    },
 }
 
 #[derive(clap::ValueEnum, Debug, Clone)]
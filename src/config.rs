@@ -3,6 +3,7 @@ use figment::{
     Figment,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use crate::error::Result;
@@ -12,6 +13,57 @@ pub struct AppConfig {
     pub ollama: OllamaConfig,
     pub db: DbConfig,
     pub index: IndexConfig,
+    pub embed: EmbedConfig,
+    pub cache: CacheConfig,
+    /// User-defined command shortcuts, e.g. `f = ["find", "--limit", "20"]`
+    /// lets `maharajah f foo` expand to `maharajah find --limit 20 foo`. See
+    /// `main::expand_aliases` for resolution rules.
+    pub alias: HashMap<String, Vec<String>>,
+    pub retrieve: RetrieveConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetrieveConfig {
+    /// Default number of results for `find`, used when `--limit` isn't passed.
+    pub result_limit: usize,
+    /// Drop any result scoring below this cutoff before it's shown. 0.0
+    /// (the default) keeps every result regardless of score.
+    pub min_score: f32,
+    /// Languages excluded from every `find` search in addition to whatever
+    /// `--exclude-lang` passes at the CLI.
+    pub exclude_languages: Vec<String>,
+    /// Score bonus added per query term that appears in a result's
+    /// `Chunk.symbol` (see `rag::retriever::apply_symbol_boost`), so
+    /// exact-name hits outrank chunks that only match on content/summary.
+    /// 0.0 (the default) disables boosting.
+    pub symbol_boost: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// Whether the content-addressed embedding cache (see `db::cache::EmbeddingCache`)
+    /// is consulted/populated during indexing. Disabling forces every chunk to
+    /// be re-embedded every run — mostly useful for benchmarking or working
+    /// around a suspected-stale cache without clearing it.
+    pub enabled: bool,
+    /// Soft cap on cached vectors, enforced by `maharajah db cache prune`
+    /// rather than automatically on every write. Entries carry no
+    /// last-used timestamp, so which ones get dropped once over the cap is
+    /// unspecified — this bounds the cache's size, not its recency.
+    pub max_entries: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbedConfig {
+    /// Which embedding backend to use: "ollama", "unixcoder", or "nomic".
+    pub provider: String,
+    /// UniXcoder model variant ("base" or "nine"), only used when
+    /// `provider = "unixcoder"`.
+    pub unixcoder_variant: String,
+    /// Number of embedder worker threads the server pool spins up. `None`
+    /// (the default) resolves via `resolve_embed_pool_size()` to the number
+    /// of logical CPUs the process may use, not the physical core count.
+    pub pool_size: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,16 +84,54 @@ pub struct DbConfig {
     pub table_name: String,
     /// Embedding vector dimensionality (must match embed_model output)
     pub embedding_dim: usize,
+    /// Vector store backend address, e.g. `lance:///path/to/db` or
+    /// `memory://`. `None` (the default) resolves to the on-disk LanceDB
+    /// store under `<target-dir>/.maharajah/db`; see `Store::from_addr`.
+    pub addr: Option<String>,
+    /// Row count at which `vector`/`summary_vector` get an IVF_PQ index built
+    /// automatically at the end of an `index` run (`refresh` does not build
+    /// it). Below this, brute-force
+    /// scan is fast enough that an ANN index would only add build overhead.
+    pub ann_index_threshold: usize,
+    /// Number of IVF partitions to probe per ANN query — higher trades latency
+    /// for recall. Ignored until an index actually exists on the column.
+    pub ann_nprobes: usize,
+    /// Oversample factor for re-ranking ANN candidates against full vectors —
+    /// higher trades latency for recall. Ignored until an index exists.
+    pub ann_refine_factor: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexConfig {
     /// Default maximum lines per chunk when tree-sitter node is too large
     pub max_chunk_lines: usize,
+    /// Trailing lines of one line-split window repeated at the head of the
+    /// next (see `chunker::split_by_lines`), so a symbol split across a
+    /// chunk boundary keeps some local context on both sides of the seam.
+    pub chunk_overlap_lines: usize,
+    /// Token budget per chunk for the line-split fallback packer (see
+    /// `chunker::split_by_lines`) — a secondary cap alongside `max_chunk_lines`
+    /// so a few lines of unusually dense code can't silently overflow an
+    /// embedding model's context window. Estimated with `chunker::estimate_tokens`,
+    /// not an exact tokenizer count.
+    pub max_chunk_tokens: usize,
+    /// Token budget per embedding batch — chunks are packed into batches that
+    /// stay under this limit rather than embedded one at a time.
+    pub max_embed_tokens: usize,
     /// File extensions to auto-include when no --include glob is given
     pub default_extensions: Vec<String>,
     /// Glob patterns for paths to exclude from indexing
     pub default_excludes: Vec<String>,
+    /// Directory containing user-supplied `<lang>.scm` tree-sitter query
+    /// files (capture names `@symbol.def`, `@symbol.name`, `@doc`) that
+    /// override the crate's built-in per-language extraction — lets teams
+    /// index constructs the crate doesn't special-case without patching it.
+    /// `None` (the default) resolves to `config::query_dir_path()`.
+    pub query_dir: Option<PathBuf>,
+    /// Honor `.gitignore`/`.ignore`/global gitignore rules while walking
+    /// `target_dir`, on top of `default_excludes`. Overridable per-run via
+    /// `index --no-ignore`.
+    pub respect_gitignore: bool,
 }
 
 impl Default for AppConfig {
@@ -56,9 +146,32 @@ impl Default for AppConfig {
             db: DbConfig {
                 table_name: "chunks".into(),
                 embedding_dim: 768,
+                addr: None,
+                ann_index_threshold: 50_000,
+                ann_nprobes: 20,
+                ann_refine_factor: 10,
+            },
+            embed: EmbedConfig {
+                provider: "unixcoder".into(),
+                unixcoder_variant: "base".into(),
+                pool_size: None,
+            },
+            cache: CacheConfig {
+                enabled: true,
+                max_entries: 100_000,
+            },
+            alias: HashMap::new(),
+            retrieve: RetrieveConfig {
+                result_limit: 10,
+                min_score: 0.0,
+                exclude_languages: Vec::new(),
+                symbol_boost: 0.0,
             },
             index: IndexConfig {
                 max_chunk_lines: 40,
+                chunk_overlap_lines: 5,
+                max_chunk_tokens: 512,
+                max_embed_tokens: 2048,
                 default_extensions: vec![
                     "rs".into(),
                     "py".into(),
@@ -101,6 +214,8 @@ impl Default for AppConfig {
                     "**/bin/Release/**".into(),
                     "**/obj/**".into(),
                 ],
+                query_dir: None,
+                respect_gitignore: true,
             },
         }
     }
@@ -114,6 +229,45 @@ pub fn global_config_path() -> PathBuf {
         .join("maharajah.toml")
 }
 
+/// Default directory for user-supplied tree-sitter query files, used when
+/// `config.index.query_dir` is unset: ~/.maharajah/queries
+pub fn query_dir_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".maharajah")
+        .join("queries")
+}
+
+/// Resolve the directory to check for `<lang>.scm` query files: an explicit
+/// `config.index.query_dir` wins, otherwise `query_dir_path()`.
+pub fn resolve_query_dir(config: &AppConfig) -> PathBuf {
+    config.index.query_dir.clone().unwrap_or_else(query_dir_path)
+}
+
+/// Resolve the number of embedder worker threads the server pool should
+/// spin up: an explicit `config.embed.pool_size` wins, otherwise
+/// `std::thread::available_parallelism()` — logical CPUs, so SMT siblings
+/// count separately rather than as one physical core (falling back to 1 if
+/// that can't be determined).
+pub fn resolve_embed_pool_size(config: &AppConfig) -> usize {
+    config.embed.pool_size.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    })
+}
+
+/// Resolve the vector store address to open: an explicit `config.db.addr`
+/// (or CLI override) wins; otherwise fall back to the on-disk LanceDB store
+/// under `db_path`.
+pub fn resolve_store_addr(config: &AppConfig, db_path: &Path) -> String {
+    config
+        .db
+        .addr
+        .clone()
+        .unwrap_or_else(|| crate::db::store::lance_addr(db_path))
+}
+
 /// Returns the LanceDB directory path for a given target directory.
 /// The database lives at <target_dir>/.maharajah/db (a directory, not a file).
 pub fn db_path(target_dir: &Path) -> PathBuf {
@@ -146,9 +300,23 @@ timeout_secs = 120
 [db]
 table_name = "chunks"
 embedding_dim = 768
+ann_index_threshold = 50000
+ann_nprobes = 20
+ann_refine_factor = 10
+
+[embed]
+provider = "unixcoder"
+unixcoder_variant = "base"
+
+[cache]
+enabled = true
+max_entries = 100000
 
 [index]
 max_chunk_lines = 40
+chunk_overlap_lines = 5
+max_chunk_tokens = 512
+max_embed_tokens = 2048
 default_extensions = ["rs", "py", "js", "jsx", "ts", "tsx", "go", "java", "cs", "fs", "fsx", "scala", "hs", "rb"]
 default_excludes = [
     "**/target/**",
@@ -168,6 +336,17 @@ default_excludes = [
     "**/bin/Release/**",
     "**/obj/**",
 ]
+respect_gitignore = true
+
+[retrieve]
+result_limit = 10
+min_score = 0.0
+exclude_languages = []
+symbol_boost = 0.0
+
+# Custom command shortcuts, e.g.:
+# [alias]
+# f = ["find", "--limit", "20"]
 "#;
 
 /// Load configuration using figment's layered system:
@@ -196,6 +375,11 @@ pub fn apply_cli_overrides(
     config: &mut AppConfig,
     ollama_url: Option<String>,
     embed_model: Option<String>,
+    db_addr: Option<String>,
+    result_limit: Option<usize>,
+    min_score: Option<f32>,
+    exclude_languages: Vec<String>,
+    symbol_boost: Option<f32>,
 ) {
     if let Some(url) = ollama_url {
         config.ollama.base_url = url;
@@ -203,4 +387,19 @@ pub fn apply_cli_overrides(
     if let Some(model) = embed_model {
         config.ollama.embed_model = model;
     }
+    if let Some(addr) = db_addr {
+        config.db.addr = Some(addr);
+    }
+    if let Some(limit) = result_limit {
+        config.retrieve.result_limit = limit;
+    }
+    if let Some(score) = min_score {
+        config.retrieve.min_score = score;
+    }
+    if !exclude_languages.is_empty() {
+        config.retrieve.exclude_languages.extend(exclude_languages);
+    }
+    if let Some(boost) = symbol_boost {
+        config.retrieve.symbol_boost = boost;
+    }
 }
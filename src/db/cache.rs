@@ -0,0 +1,199 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::{
+    builder::{FixedSizeListBuilder, Float32Builder, StringBuilder},
+    Array, RecordBatch, RecordBatchIterator, StringArray,
+};
+use arrow_schema::{ArrowError, DataType, Field, Fields, Schema};
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+use crate::error::{AppError, Result};
+
+/// A persistent, content-addressed cache of chunk embeddings.
+///
+/// Keyed by `(content_hash, model_id, embedding_dim)` so that switching the
+/// configured embedding model *or* its output dimension invalidates every
+/// previously cached vector without an explicit migration step — a lookup
+/// under the new model id / dim is simply a miss, which is exactly what
+/// `index --reindex` after a model change needs.
+pub struct EmbeddingCache {
+    table: lancedb::Table,
+    embedding_dim: usize,
+}
+
+/// Build the cache's row key from a chunk content hash, model identifier and
+/// embedding dimension. Folding all three into one string lets the cache live
+/// in a single-column-keyed table, mirroring how `Store` keys chunk rows as
+/// `file_path:start_line`.
+fn cache_key(content_hash: &str, model_id: &str, embedding_dim: usize) -> String {
+    format!("{model_id}:{embedding_dim}:{content_hash}")
+}
+
+fn cache_schema(embedding_dim: usize) -> Arc<Schema> {
+    Arc::new(Schema::new(Fields::from(vec![
+        Field::new("key", DataType::Utf8, false),
+        Field::new(
+            "vector",
+            DataType::FixedSizeList(
+                Arc::new(Field::new("item", DataType::Float32, true)),
+                embedding_dim as i32,
+            ),
+            false,
+        ),
+    ])))
+}
+
+impl EmbeddingCache {
+    pub async fn open_or_create(
+        db_path: &Path,
+        embedding_dim: usize,
+        table_name: &str,
+    ) -> Result<Self> {
+        let uri = db_path.to_str().expect("db path is not valid UTF-8");
+        let conn = lancedb::connect(uri).execute().await?;
+
+        let schema = cache_schema(embedding_dim);
+        let table = match conn.open_table(table_name).execute().await {
+            Ok(t) => t,
+            Err(lancedb::Error::TableNotFound { .. }) => {
+                conn.create_empty_table(table_name, schema).execute().await?
+            }
+            Err(e) => return Err(AppError::Database(e)),
+        };
+
+        Ok(Self {
+            table,
+            embedding_dim,
+        })
+    }
+
+    /// Look up a previously embedded vector for `content_hash` under `model_id`.
+    pub async fn get(&self, content_hash: &str, model_id: &str) -> Result<Option<Vec<f32>>> {
+        let key = cache_key(content_hash, model_id, self.embedding_dim);
+        let escaped = key.replace('\'', "''");
+        let mut stream = self
+            .table
+            .query()
+            .only_if(format!("key = '{escaped}'"))
+            .limit(1)
+            .execute()
+            .await?;
+
+        while let Some(batch) = stream.try_next().await? {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            if let Some(col) = batch.column_by_name("vector") {
+                if let Some(list) = col
+                    .as_any()
+                    .downcast_ref::<arrow_array::FixedSizeListArray>()
+                {
+                    let values = list.value(0);
+                    if let Some(floats) = values.as_any().downcast_ref::<arrow_array::Float32Array>()
+                    {
+                        return Ok(Some(floats.values().to_vec()));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Insert a freshly computed vector into the cache, keyed by content hash
+    /// and model id. Overwrites any stale entry for the same key.
+    pub async fn put(&self, content_hash: &str, model_id: &str, vector: &[f32]) -> Result<()> {
+        let key = cache_key(content_hash, model_id, self.embedding_dim);
+        let escaped = key.replace('\'', "''");
+        self.table.delete(&format!("key = '{escaped}'")).await?;
+
+        let schema = cache_schema(self.embedding_dim);
+        let mut key_builder = StringBuilder::new();
+        let mut vector_builder =
+            FixedSizeListBuilder::new(Float32Builder::new(), self.embedding_dim as i32);
+
+        key_builder.append_value(&key);
+        for &v in vector {
+            vector_builder.values().append_value(v);
+        }
+        vector_builder.append(true);
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(key_builder.finish()),
+                Arc::new(vector_builder.finish()),
+            ],
+        )
+        .map_err(|e| AppError::Other(e.into()))?;
+
+        let reader = RecordBatchIterator::new(
+            vec![Ok(batch) as std::result::Result<RecordBatch, ArrowError>],
+            schema,
+        );
+        self.table.add(reader).execute().await?;
+        Ok(())
+    }
+
+    /// Number of cached vectors, for `maharajah db cache stats`.
+    pub async fn count(&self) -> Result<usize> {
+        let mut total = 0usize;
+        let mut stream = self
+            .table
+            .query()
+            .select(lancedb::query::Select::Columns(vec!["key".into()]))
+            .execute()
+            .await?;
+        while let Some(batch) = stream.try_next().await? {
+            total += batch.num_rows();
+        }
+        Ok(total)
+    }
+
+    /// Drop cache entries down to at most `max_entries`, for
+    /// `maharajah db cache prune`. Returns the number of entries removed.
+    /// Entries carry no last-used timestamp, so which ones survive once over
+    /// the cap is unspecified — this bounds the cache's size, not its recency.
+    pub async fn prune_to(&self, max_entries: usize) -> Result<usize> {
+        let mut keys = Vec::new();
+        let mut stream = self
+            .table
+            .query()
+            .select(lancedb::query::Select::Columns(vec!["key".into()]))
+            .execute()
+            .await?;
+        while let Some(batch) = stream.try_next().await? {
+            if let Some(col) = batch.column_by_name("key") {
+                if let Some(arr) = col.as_any().downcast_ref::<StringArray>() {
+                    for i in 0..arr.len() {
+                        if !arr.is_null(i) {
+                            keys.push(arr.value(i).to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        if keys.len() <= max_entries {
+            return Ok(0);
+        }
+
+        let excess = &keys[max_entries..];
+        for key in excess {
+            let escaped = key.replace('\'', "''");
+            self.table.delete(&format!("key = '{escaped}'")).await?;
+        }
+        Ok(excess.len())
+    }
+}
+
+/// Hash a single chunk's content (SHA-256), for cache keying.
+/// Reuses the same digest the indexer uses for whole-file hashes.
+pub fn hash_content(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
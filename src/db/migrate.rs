@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use crate::config::AppConfig;
+use crate::db::schema::chunks_schema;
+use crate::db::store::{ChunkRecord, Store};
+use crate::embed;
+use crate::error::{AppError, Result};
+
+/// Re-embed every stored chunk with the embedder configured in `config` and
+/// swap it in for the existing table, so switching between Nomic/Ollama/
+/// UniXcoder (or any change of `embedding_dim`) doesn't require re-indexing
+/// source files from scratch.
+///
+/// The user is expected to have already pointed `config.embed`/
+/// `config.db.embedding_dim` at the new provider — `embed::build` validates
+/// the new embedder's dimensionality against `config.db.embedding_dim` up
+/// front, the same check it performs for every other command.
+///
+/// The swap is best-effort atomic: all chunks are re-embedded and staged in
+/// memory first, and the old table is only dropped once the new data is
+/// ready to insert under the same name. A crash between the drop and the
+/// insert would leave the table empty; there's no LanceDB table-rename
+/// primitive this crate can lean on to avoid that narrow window.
+pub async fn run(config: &AppConfig, db_path: &Path) -> Result<()> {
+    let addr = crate::config::resolve_store_addr(config, db_path);
+    let table_name = &config.db.table_name;
+
+    let old_store = match Store::try_from_addr(&addr, config.db.embedding_dim, table_name).await? {
+        Some(store) => store,
+        None => {
+            return Err(AppError::Other(anyhow::anyhow!(
+                "no index found at '{addr}' — run `index` first"
+            )));
+        }
+    };
+
+    let chunks = old_store.scan_all().await?;
+    println!("Re-embedding {} chunk(s)...", chunks.len());
+
+    let embedder = embed::build(config).await?;
+    let new_dim = embedder.dimension();
+
+    let contents: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+    let vectors = embedder.embed_code_batch(&contents).await?;
+
+    let summary_indices: Vec<usize> = chunks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| c.summary.is_some().then_some(i))
+        .collect();
+    let summaries: Vec<String> = summary_indices
+        .iter()
+        .map(|&i| chunks[i].summary.clone().expect("filtered to Some above"))
+        .collect();
+    let summary_vectors = embedder.embed_code_batch(&summaries).await?;
+    let mut summary_vector_by_index: std::collections::HashMap<usize, Vec<f32>> =
+        std::collections::HashMap::new();
+    for (&i, v) in summary_indices.iter().zip(summary_vectors) {
+        summary_vector_by_index.insert(i, v);
+    }
+
+    let migrated: Vec<ChunkRecord> = chunks
+        .into_iter()
+        .zip(vectors)
+        .enumerate()
+        .map(|(i, (chunk, vector))| ChunkRecord {
+            summary_vector: summary_vector_by_index.remove(&i),
+            vector,
+            ..chunk
+        })
+        .collect();
+
+    let uri = crate::db::store::resolve_uri(&addr)?;
+    let conn = lancedb::connect(&uri).execute().await?;
+    let _ = conn.drop_table(table_name, &[]).await;
+    let table = conn
+        .create_empty_table(table_name, chunks_schema(new_dim))
+        .execute()
+        .await?;
+    let new_store = Store::from_table(table, new_dim);
+    new_store.insert(&migrated).await?;
+
+    println!("Migration complete: {table_name} now holds {new_dim}-dim vectors.");
+    Ok(())
+}
@@ -0,0 +1,4 @@
+pub mod cache;
+pub mod migrate;
+pub mod schema;
+pub mod store;
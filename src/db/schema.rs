@@ -4,16 +4,28 @@ use std::sync::Arc;
 /// Arrow schema for indexed code chunks stored in LanceDB.
 ///
 /// Columns:
-/// - id         : unique chunk identifier (file_path:start_line)
-/// - file_path  : source file path
-/// - file_hash  : SHA-256 hex of file content (for incremental updates)
-/// - language   : detected language (rust, python, ...)
-/// - symbol     : tree-sitter node name (fn/class name, or empty string)
-/// - content    : raw source text of the chunk
-/// - start_line : 0-based start line in file
-/// - end_line   : 0-based end line in file
-/// - summary    : extracted docstring/comment summary (nullable)
-/// - vector     : embedding vector (FixedSizeList<Float32>)
+/// - id               : unique chunk identifier (file_path:start_line)
+/// - file_path        : source file path
+/// - file_hash        : SHA-256 hex of file content (for incremental updates)
+/// - language         : detected language (rust, python, ...)
+/// - symbol           : tree-sitter node name (fn/class name, or empty string)
+/// - qualified_symbol : dotted/namespaced path to the symbol (falls back to
+///   `symbol` when the language has no containing namespace)
+/// - parent_symbol    : enclosing container's `symbol` (nullable — absent at
+///   the top level)
+/// - kind             : `SymbolKind::as_str()` (function, struct, ...)
+/// - visibility       : `Visibility::as_str()` (public, private, ...)
+/// - signature        : extracted function/method signature (nullable)
+/// - content          : raw source text of the chunk
+/// - start_line       : 0-based start line in file
+/// - end_line         : 0-based end line in file
+/// - summary          : extracted docstring/comment summary (nullable)
+/// - references       : bare names of symbols this chunk calls (List<Utf8>)
+/// - doc_links        : symbols cross-referenced from this chunk's doc
+///   comments (List<Utf8>)
+/// - vector           : embedding vector of `content` (FixedSizeList<Float32>)
+/// - summary_vector   : embedding vector of `summary` (FixedSizeList<Float32>,
+///   nullable — absent when the chunk has no summary)
 pub fn chunks_schema(embedding_dim: usize) -> Arc<Schema> {
     Arc::new(Schema::new(Fields::from(vec![
         Field::new("id", DataType::Utf8, false),
@@ -21,10 +33,25 @@ pub fn chunks_schema(embedding_dim: usize) -> Arc<Schema> {
         Field::new("file_hash", DataType::Utf8, false),
         Field::new("language", DataType::Utf8, false),
         Field::new("symbol", DataType::Utf8, false),
+        Field::new("qualified_symbol", DataType::Utf8, false),
+        Field::new("parent_symbol", DataType::Utf8, true),
+        Field::new("kind", DataType::Utf8, false),
+        Field::new("visibility", DataType::Utf8, false),
+        Field::new("signature", DataType::Utf8, true),
         Field::new("content", DataType::Utf8, false),
         Field::new("start_line", DataType::UInt32, false),
         Field::new("end_line", DataType::UInt32, false),
         Field::new("summary", DataType::Utf8, true),
+        Field::new(
+            "references",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+        Field::new(
+            "doc_links",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
         Field::new(
             "vector",
             DataType::FixedSizeList(
@@ -33,5 +60,13 @@ pub fn chunks_schema(embedding_dim: usize) -> Arc<Schema> {
             ),
             false,
         ),
+        Field::new(
+            "summary_vector",
+            DataType::FixedSizeList(
+                Arc::new(Field::new("item", DataType::Float32, true)),
+                embedding_dim as i32,
+            ),
+            true,
+        ),
     ])))
 }
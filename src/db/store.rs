@@ -2,11 +2,14 @@ use std::path::Path;
 use std::sync::Arc;
 
 use arrow_array::{
-    Array, Float32Array, RecordBatch, RecordBatchIterator, StringArray, UInt32Array,
-    builder::{FixedSizeListBuilder, Float32Builder, StringBuilder, UInt32Builder},
+    Array, FixedSizeListArray, Float32Array, ListArray, RecordBatch, RecordBatchIterator,
+    StringArray, UInt32Array,
+    builder::{FixedSizeListBuilder, Float32Builder, ListBuilder, StringBuilder, UInt32Builder},
 };
 use arrow_schema::ArrowError;
 use futures::TryStreamExt;
+use lancedb::index::Index;
+use lancedb::index::vector::IvfPqIndexBuilder;
 use lancedb::query::{ExecutableQuery, QueryBase};
 
 use crate::db::schema::chunks_schema;
@@ -18,11 +21,18 @@ pub struct ChunkRecord {
     pub file_hash: String,
     pub language: String,
     pub symbol: String,
+    pub qualified_symbol: String,
+    pub parent_symbol: Option<String>,
+    pub kind: String,
+    pub visibility: String,
+    pub signature: Option<String>,
     pub content: String,
     pub start_line: u32,
     pub end_line: u32,
     pub vector: Vec<f32>,
     pub summary: Option<String>,
+    pub references: Vec<String>,
+    pub doc_links: Vec<String>,
     pub summary_vector: Option<Vec<f32>>,
 }
 
@@ -30,28 +40,134 @@ pub struct ChunkRecord {
 pub struct SearchResult {
     pub id: String,
     pub file_path: String,
+    pub language: String,
     pub start_line: u32,
     pub end_line: u32,
     pub symbol: String,
+    pub qualified_symbol: String,
+    pub parent_symbol: Option<String>,
+    pub kind: String,
+    pub visibility: String,
+    pub signature: Option<String>,
     pub content: String,
     pub score: f32,
     pub summary: Option<String>,
+    pub references: Vec<String>,
+    pub doc_links: Vec<String>,
+    /// The stored `vector` embedding, populated by `search()` so MMR
+    /// reranking (see `rag::retriever::mmr_rerank`) can compute
+    /// candidate-to-candidate similarity without a second fetch. `None` for
+    /// results from searches that don't read the `vector` column.
+    #[serde(skip_serializing)]
+    pub vector: Option<Vec<f32>>,
+}
+
+/// Structured constraints to narrow a search before ranking, so `limit`
+/// applies to the already-scoped result set rather than the whole table.
+#[derive(Default, Clone)]
+pub struct SearchFilters {
+    /// Exact `language` match (e.g. `"rust"`).
+    pub language: Option<String>,
+    /// Glob over `file_path` (e.g. `"src/db/**"`), compiled to a SQL `LIKE`.
+    pub path_glob: Option<String>,
+    /// Prefix match on `qualified_symbol` (e.g. `"Store::"`).
+    pub symbol_prefix: Option<String>,
+    /// Languages to drop regardless of `language` (e.g. config-wide noise
+    /// like generated `.pb.go` or vendored `.proto` stubs).
+    pub exclude_languages: Vec<String>,
+}
+
+impl SearchFilters {
+    /// Compose the configured constraints into a single `only_if` predicate,
+    /// or `None` when no filter is set.
+    fn to_predicate(&self) -> Option<String> {
+        let mut clauses = Vec::new();
+        if let Some(language) = &self.language {
+            clauses.push(format!("language = '{}'", escape_sql(language)));
+        }
+        if let Some(glob) = &self.path_glob {
+            clauses.push(format!("file_path LIKE '{}'", escape_sql(&glob_to_like(glob))));
+        }
+        if let Some(prefix) = &self.symbol_prefix {
+            clauses.push(format!("qualified_symbol LIKE '{}'", escape_sql(&format!("{prefix}%"))));
+        }
+        for language in &self.exclude_languages {
+            clauses.push(format!("language != '{}'", escape_sql(language)));
+        }
+        (!clauses.is_empty()).then(|| clauses.join(" AND "))
+    }
+}
+
+/// Escape a value for embedding in a single-quoted SQL string literal.
+fn escape_sql(s: &str) -> String {
+    s.replace('\'', "''")
+}
+
+/// Compile a `**`/`*` glob into a SQL `LIKE` pattern: `**` and `*` both become
+/// `%`, since LanceDB's LIKE has no path-aware wildcard to distinguish them.
+fn glob_to_like(glob: &str) -> String {
+    glob.replace("**", "%").replace('*', "%")
+}
+
+/// AND two optional `only_if` predicates together, passing through whichever
+/// side is present when the other is absent.
+fn and_predicates(a: Option<String>, b: Option<String>) -> Option<String> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(format!("({a}) AND ({b})")),
+        (Some(a), None) => Some(a),
+        (None, Some(b)) => Some(b),
+        (None, None) => None,
+    }
 }
 
 pub struct Store {
     table: lancedb::Table,
     embedding_dim: usize,
+    nprobes: Option<usize>,
+    refine_factor: Option<u32>,
+}
+
+/// Turn a store address into the URI `lancedb::connect` should open.
+///
+/// Mirrors the `from_addr` convention used by tvix-castore's blob/directory
+/// services: a URI scheme selects the backend, with everything after it
+/// passed straight through to the backend.
+///
+/// - `lance:///absolute/path` — the on-disk LanceDB store at that path.
+/// - `memory://` — a purely in-memory store, gone once the connection drops.
+///   Handy for unit tests, CI, and one-shot `find` runs that shouldn't leave
+///   a `.maharajah/db` behind.
+///
+/// Any other address, including LanceDB's own object-store URIs (e.g.
+/// `s3://`, `gs://`), is rejected with an error.
+pub(crate) fn resolve_uri(addr: &str) -> Result<String> {
+    if let Some(path) = addr.strip_prefix("lance://") {
+        Ok(path.to_string())
+    } else if addr == "memory://" || addr.starts_with("memory://") {
+        Ok("memory://".to_string())
+    } else {
+        Err(AppError::Other(anyhow::anyhow!(
+            "unsupported store address '{addr}' (expected lance://<path> or memory://)"
+        )))
+    }
+}
+
+/// Build a `lance://` address for the on-disk store under `db_path`.
+pub fn lance_addr(db_path: &Path) -> String {
+    format!("lance://{}", db_path.to_str().expect("db path is not valid UTF-8"))
 }
 
 impl Store {
-    pub async fn open_or_create(
-        db_path: &Path,
+    /// Open (or create) the store identified by `addr` — see [`resolve_uri`]
+    /// for the supported schemes.
+    pub async fn from_addr(
+        addr: &str,
         embedding_dim: usize,
         table_name: &str,
         reindex: bool,
     ) -> Result<Self> {
-        let uri = db_path.to_str().expect("db path is not valid UTF-8");
-        let conn = lancedb::connect(uri).execute().await?;
+        let uri = resolve_uri(addr)?;
+        let conn = lancedb::connect(&uri).execute().await?;
 
         if reindex {
             let _ = conn.drop_table(table_name, &[]).await;
@@ -74,23 +190,70 @@ impl Store {
         Ok(Store {
             table,
             embedding_dim,
+            nprobes: None,
+            refine_factor: None,
         })
     }
 
-    pub async fn try_open(
-        db_path: &Path,
+    pub async fn try_from_addr(
+        addr: &str,
         embedding_dim: usize,
         table_name: &str,
     ) -> Result<Option<Self>> {
-        let uri = db_path.to_str().expect("db path is not valid UTF-8");
-        let conn = lancedb::connect(uri).execute().await?;
+        let uri = resolve_uri(addr)?;
+        let conn = lancedb::connect(&uri).execute().await?;
         match conn.open_table(table_name).execute().await {
-            Ok(table) => Ok(Some(Store { table, embedding_dim })),
+            Ok(table) => Ok(Some(Store {
+                table,
+                embedding_dim,
+                nprobes: None,
+                refine_factor: None,
+            })),
             Err(lancedb::Error::TableNotFound { .. }) => Ok(None),
             Err(e) => Err(AppError::Database(e)),
         }
     }
 
+    /// Tune ANN recall/latency trade-off for subsequent [`Store::search`] /
+    /// [`Store::search_by_summary`] calls. A no-op until an index actually
+    /// exists on the queried column (see [`Store::build_indexes_if_needed`]) —
+    /// LanceDB ignores these knobs during a brute-force scan.
+    pub fn with_ann_params(mut self, nprobes: usize, refine_factor: u32) -> Self {
+        self.nprobes = Some(nprobes);
+        self.refine_factor = Some(refine_factor);
+        self
+    }
+
+    /// Convenience wrapper over [`Store::from_addr`] for the common case of an
+    /// on-disk store at a filesystem path.
+    pub async fn open_or_create(
+        db_path: &Path,
+        embedding_dim: usize,
+        table_name: &str,
+        reindex: bool,
+    ) -> Result<Self> {
+        Self::from_addr(&lance_addr(db_path), embedding_dim, table_name, reindex).await
+    }
+
+    pub async fn try_open(
+        db_path: &Path,
+        embedding_dim: usize,
+        table_name: &str,
+    ) -> Result<Option<Self>> {
+        Self::try_from_addr(&lance_addr(db_path), embedding_dim, table_name).await
+    }
+
+    /// Wrap an already-open table, e.g. a scratch table created by
+    /// `db::migrate` for re-embedding into a new `embedding_dim`.
+    pub(crate) fn from_table(table: lancedb::Table, embedding_dim: usize) -> Self {
+        Store {
+            table,
+            embedding_dim,
+            nprobes: None,
+            refine_factor: None,
+        }
+    }
+
     pub async fn count_rows(&self) -> Result<usize> {
         let mut total = 0usize;
         let mut stream = self.table.query().execute().await?;
@@ -100,6 +263,38 @@ impl Store {
         Ok(total)
     }
 
+    /// Build (or rebuild) an IVF_PQ ANN index on `column`. Brute-force scan is
+    /// fine for a few thousand rows but degrades linearly, so large tables
+    /// need this to keep `search`/`search_by_summary` fast.
+    pub async fn create_vector_index(&self, column: &str) -> Result<()> {
+        self.table
+            .create_index(&[column], Index::IvfPq(IvfPqIndexBuilder::default()))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// Build ANN indexes on `vector` and `summary_vector` once the table
+    /// crosses `threshold` rows, as called automatically at the end of an
+    /// `index` run (`refresh` does not call it). Returns whether a build was
+    /// attempted.
+    ///
+    /// `summary_vector` is best-effort: chunks without a summary leave it
+    /// null, and a table that's all-null in that column can't train an IVF_PQ
+    /// index, so a failure there is logged and swallowed rather than failing
+    /// the whole run.
+    pub async fn build_indexes_if_needed(&self, threshold: usize) -> Result<bool> {
+        if self.count_rows().await? < threshold {
+            return Ok(false);
+        }
+
+        self.create_vector_index("vector").await?;
+        if let Err(e) = self.create_vector_index("summary_vector").await {
+            tracing::warn!("skipping summary_vector ANN index: {e}");
+        }
+        Ok(true)
+    }
+
     pub async fn list_files(&self) -> Result<std::collections::HashSet<String>> {
         let mut files = std::collections::HashSet::new();
         let mut stream = self
@@ -126,13 +321,71 @@ impl Store {
         Ok(self.list_files().await?.len())
     }
 
+    /// Scan every row of the table, for re-embedding into a differently
+    /// configured embedder (see `db::migrate`). `vector`/`summary_vector` are
+    /// left empty/`None` placeholders — the caller re-embeds `content` and
+    /// `summary` itself rather than reading back the old vectors.
+    pub async fn scan_all(&self) -> Result<Vec<ChunkRecord>> {
+        let mut records = Vec::new();
+        let mut stream = self
+            .table
+            .query()
+            .select(lancedb::query::Select::Columns(vec![
+                "id".into(),
+                "file_path".into(),
+                "file_hash".into(),
+                "language".into(),
+                "symbol".into(),
+                "qualified_symbol".into(),
+                "parent_symbol".into(),
+                "kind".into(),
+                "visibility".into(),
+                "signature".into(),
+                "content".into(),
+                "start_line".into(),
+                "end_line".into(),
+                "summary".into(),
+                "references".into(),
+                "doc_links".into(),
+            ]))
+            .execute()
+            .await?;
+
+        while let Some(batch) = stream.try_next().await? {
+            for i in 0..batch.num_rows() {
+                records.push(ChunkRecord {
+                    id: get_str_col(&batch, "id", i)?,
+                    file_path: get_str_col(&batch, "file_path", i)?,
+                    file_hash: get_str_col(&batch, "file_hash", i)?,
+                    language: get_str_col(&batch, "language", i)?,
+                    symbol: get_str_col(&batch, "symbol", i)?,
+                    qualified_symbol: get_str_col(&batch, "qualified_symbol", i)?,
+                    parent_symbol: get_nullable_str_col(&batch, "parent_symbol", i)?,
+                    kind: get_str_col(&batch, "kind", i)?,
+                    visibility: get_str_col(&batch, "visibility", i)?,
+                    signature: get_nullable_str_col(&batch, "signature", i)?,
+                    content: get_str_col(&batch, "content", i)?,
+                    start_line: get_u32_col(&batch, "start_line", i)?,
+                    end_line: get_u32_col(&batch, "end_line", i)?,
+                    vector: Vec::new(),
+                    summary: get_nullable_str_col(&batch, "summary", i)?,
+                    references: get_str_list_col(&batch, "references", i)?,
+                    doc_links: get_str_list_col(&batch, "doc_links", i)?,
+                    summary_vector: None,
+                });
+            }
+        }
+
+        Ok(records)
+    }
+
     pub async fn clear(&self) -> Result<()> {
         self.table.delete("1 = 1").await?;
         Ok(())
     }
 
     pub async fn get_file_hash(&self, file_path: &str) -> Result<Option<String>> {
-        let escaped = file_path.replace('\'', "''");
+        let escaped = escape_sql(file_path);
         let mut stream = self
             .table
             .query()
@@ -157,7 +410,7 @@ impl Store {
     }
 
     pub async fn delete_file(&self, file_path: &str) -> Result<()> {
-        let escaped = file_path.replace('\'', "''");
+        let escaped = escape_sql(file_path);
         self.table
             .delete(&format!("file_path = '{}'", escaped))
             .await?;
@@ -176,10 +429,17 @@ impl Store {
         let mut file_hash_builder = StringBuilder::new();
         let mut language_builder = StringBuilder::new();
         let mut symbol_builder = StringBuilder::new();
+        let mut qualified_symbol_builder = StringBuilder::new();
+        let mut parent_symbol_builder = StringBuilder::new();
+        let mut kind_builder = StringBuilder::new();
+        let mut visibility_builder = StringBuilder::new();
+        let mut signature_builder = StringBuilder::new();
         let mut content_builder = StringBuilder::new();
         let mut start_line_builder = UInt32Builder::new();
         let mut end_line_builder = UInt32Builder::new();
         let mut summary_builder = StringBuilder::new();
+        let mut references_builder = ListBuilder::new(StringBuilder::new());
+        let mut doc_links_builder = ListBuilder::new(StringBuilder::new());
         let mut vector_builder =
             FixedSizeListBuilder::new(Float32Builder::new(), self.embedding_dim as i32);
         let mut summary_vector_builder =
@@ -191,10 +451,23 @@ impl Store {
             file_hash_builder.append_value(&chunk.file_hash);
             language_builder.append_value(&chunk.language);
             symbol_builder.append_value(&chunk.symbol);
+            qualified_symbol_builder.append_value(&chunk.qualified_symbol);
+            parent_symbol_builder.append_option(chunk.parent_symbol.as_deref());
+            kind_builder.append_value(&chunk.kind);
+            visibility_builder.append_value(&chunk.visibility);
+            signature_builder.append_option(chunk.signature.as_deref());
             content_builder.append_value(&chunk.content);
             start_line_builder.append_value(chunk.start_line);
             end_line_builder.append_value(chunk.end_line);
             summary_builder.append_option(chunk.summary.as_deref());
+            for r in &chunk.references {
+                references_builder.values().append_value(r);
+            }
+            references_builder.append(true);
+            for l in &chunk.doc_links {
+                doc_links_builder.values().append_value(l);
+            }
+            doc_links_builder.append(true);
             for &v in &chunk.vector {
                 vector_builder.values().append_value(v);
             }
@@ -225,10 +498,17 @@ impl Store {
                 Arc::new(file_hash_builder.finish()),
                 Arc::new(language_builder.finish()),
                 Arc::new(symbol_builder.finish()),
+                Arc::new(qualified_symbol_builder.finish()),
+                Arc::new(parent_symbol_builder.finish()),
+                Arc::new(kind_builder.finish()),
+                Arc::new(visibility_builder.finish()),
+                Arc::new(signature_builder.finish()),
                 Arc::new(content_builder.finish()),
                 Arc::new(start_line_builder.finish()),
                 Arc::new(end_line_builder.finish()),
                 Arc::new(summary_builder.finish()),
+                Arc::new(references_builder.finish()),
+                Arc::new(doc_links_builder.finish()),
                 Arc::new(vector_builder.finish()),
                 Arc::new(summary_vector_builder.finish()),
             ],
@@ -244,36 +524,63 @@ impl Store {
         Ok(())
     }
 
-    pub async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
-        let mut stream = self
-            .table
-            .vector_search(vector)?
-            .column("vector")
-            .limit(limit)
-            .execute()
-            .await?;
+    pub async fn search(
+        &self,
+        vector: &[f32],
+        limit: usize,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SearchResult>> {
+        let mut query = self.table.vector_search(vector)?.column("vector").limit(limit);
+        if let Some(predicate) = filters.to_predicate() {
+            query = query.only_if(predicate);
+        }
+        if let Some(nprobes) = self.nprobes {
+            query = query.nprobes(nprobes);
+        }
+        if let Some(refine_factor) = self.refine_factor {
+            query = query.refine_factor(refine_factor);
+        }
+        let mut stream = query.execute().await?;
 
         let mut results = Vec::new();
         while let Some(batch) = stream.try_next().await? {
             for i in 0..batch.num_rows() {
                 let id = get_str_col(&batch, "id", i)?;
                 let file_path = get_str_col(&batch, "file_path", i)?;
+                let language = get_str_col(&batch, "language", i)?;
                 let symbol = get_str_col(&batch, "symbol", i)?;
+                let qualified_symbol = get_str_col(&batch, "qualified_symbol", i)?;
+                let parent_symbol = get_nullable_str_col(&batch, "parent_symbol", i)?;
+                let kind = get_str_col(&batch, "kind", i)?;
+                let visibility = get_str_col(&batch, "visibility", i)?;
+                let signature = get_nullable_str_col(&batch, "signature", i)?;
                 let content = get_str_col(&batch, "content", i)?;
                 let start_line = get_u32_col(&batch, "start_line", i)?;
                 let end_line = get_u32_col(&batch, "end_line", i)?;
                 let score = get_f32_col(&batch, "_distance", i).unwrap_or(0.0);
                 let summary = get_nullable_str_col(&batch, "summary", i)?;
+                let references = get_str_list_col(&batch, "references", i)?;
+                let doc_links = get_str_list_col(&batch, "doc_links", i)?;
+                let vector = get_vector_col(&batch, "vector", i).ok();
 
                 results.push(SearchResult {
                     id,
                     file_path,
+                    language,
                     start_line,
                     end_line,
                     symbol,
+                    qualified_symbol,
+                    parent_symbol,
+                    kind,
+                    visibility,
+                    signature,
                     content,
                     score,
                     summary,
+                    references,
+                    doc_links,
+                    vector,
                 });
             }
         }
@@ -281,47 +588,244 @@ impl Store {
         Ok(results)
     }
 
+    /// Lexical (keyword) search over `content` and `symbol`, for exact
+    /// identifier matches that embedding similarity tends to blur together.
+    ///
+    /// Scored with BM25 (`k1 = 1.2`, `b = 0.75`) over a whitespace/punctuation
+    /// tokenization of each row, with `symbol` tokens repeated
+    /// [`SYMBOL_WEIGHT`] times so an exact identifier hit like `parse_config`
+    /// outranks an incidental mention buried in `content`. Corpus statistics
+    /// (document frequency, average length) are recomputed from a full table
+    /// scan on every call rather than a persisted inverted index — fine for
+    /// the table sizes this crate targets; swapping in a LanceDB/tantivy FTS
+    /// index later only needs to change this function's body.
+    pub async fn full_text_search(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SearchResult>> {
+        const K1: f32 = 1.2;
+        const B: f32 = 0.75;
+        const SYMBOL_WEIGHT: usize = 3;
+
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut table_query = self.table.query();
+        if let Some(predicate) = filters.to_predicate() {
+            table_query = table_query.only_if(predicate);
+        }
+        let mut stream = table_query.execute().await?;
+        let mut docs: Vec<(SearchResult, Vec<String>)> = Vec::new();
+
+        while let Some(batch) = stream.try_next().await? {
+            for i in 0..batch.num_rows() {
+                let id = get_str_col(&batch, "id", i)?;
+                let file_path = get_str_col(&batch, "file_path", i)?;
+                let language = get_str_col(&batch, "language", i)?;
+                let symbol = get_str_col(&batch, "symbol", i)?;
+                let qualified_symbol = get_str_col(&batch, "qualified_symbol", i)?;
+                let parent_symbol = get_nullable_str_col(&batch, "parent_symbol", i)?;
+                let kind = get_str_col(&batch, "kind", i)?;
+                let visibility = get_str_col(&batch, "visibility", i)?;
+                let signature = get_nullable_str_col(&batch, "signature", i)?;
+                let content = get_str_col(&batch, "content", i)?;
+                let start_line = get_u32_col(&batch, "start_line", i)?;
+                let end_line = get_u32_col(&batch, "end_line", i)?;
+                let summary = get_nullable_str_col(&batch, "summary", i)?;
+                let references = get_str_list_col(&batch, "references", i)?;
+                let doc_links = get_str_list_col(&batch, "doc_links", i)?;
+
+                let mut tokens = Vec::new();
+                for _ in 0..SYMBOL_WEIGHT {
+                    tokens.extend(tokenize(&symbol));
+                }
+                tokens.extend(tokenize(&content));
+
+                docs.push((
+                    SearchResult {
+                        id,
+                        file_path,
+                        language,
+                        start_line,
+                        end_line,
+                        symbol,
+                        qualified_symbol,
+                        parent_symbol,
+                        kind,
+                        visibility,
+                        signature,
+                        content,
+                        score: 0.0,
+                        summary,
+                        references,
+                        doc_links,
+                        vector: None,
+                    },
+                    tokens,
+                ));
+            }
+        }
+
+        if docs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let doc_count = docs.len() as f32;
+        let avg_len = docs.iter().map(|(_, t)| t.len()).sum::<usize>() as f32 / doc_count;
+        let doc_freq = |term: &str| -> f32 {
+            docs.iter().filter(|(_, t)| t.iter().any(|w| w == term)).count() as f32
+        };
+
+        let mut scored: Vec<(f32, SearchResult)> = Vec::new();
+        for (mut result, tokens) in docs {
+            let doc_len = tokens.len() as f32;
+            let mut score = 0.0f32;
+            for term in &terms {
+                let tf = tokens.iter().filter(|w| *w == term).count() as f32;
+                if tf == 0.0 {
+                    continue;
+                }
+                let df = doc_freq(term);
+                let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+                score += idf * (tf * (K1 + 1.0)) / (tf + K1 * (1.0 - B + B * doc_len / avg_len));
+            }
+            if score > 0.0 {
+                result.score = score;
+                scored.push((score, result));
+            }
+        }
+
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+        Ok(scored.into_iter().map(|(_, r)| r).collect())
+    }
+
     pub async fn search_by_summary(
         &self,
         vector: &[f32],
         limit: usize,
+        filters: &SearchFilters,
     ) -> Result<Vec<SearchResult>> {
-        let mut stream = self
+        let predicate = and_predicates(Some("summary IS NOT NULL".to_string()), filters.to_predicate())
+            .expect("summary IS NOT NULL is always present");
+        let mut query = self
             .table
             .vector_search(vector)?
             .column("summary_vector")
-            .only_if("summary IS NOT NULL")
-            .limit(limit)
-            .execute()
-            .await?;
+            .only_if(predicate)
+            .limit(limit);
+        if let Some(nprobes) = self.nprobes {
+            query = query.nprobes(nprobes);
+        }
+        if let Some(refine_factor) = self.refine_factor {
+            query = query.refine_factor(refine_factor);
+        }
+        let mut stream = query.execute().await?;
 
         let mut results = Vec::new();
         while let Some(batch) = stream.try_next().await? {
             for i in 0..batch.num_rows() {
                 let id = get_str_col(&batch, "id", i)?;
                 let file_path = get_str_col(&batch, "file_path", i)?;
+                let language = get_str_col(&batch, "language", i)?;
                 let symbol = get_str_col(&batch, "symbol", i)?;
+                let qualified_symbol = get_str_col(&batch, "qualified_symbol", i)?;
+                let parent_symbol = get_nullable_str_col(&batch, "parent_symbol", i)?;
+                let kind = get_str_col(&batch, "kind", i)?;
+                let visibility = get_str_col(&batch, "visibility", i)?;
+                let signature = get_nullable_str_col(&batch, "signature", i)?;
                 let content = get_str_col(&batch, "content", i)?;
                 let start_line = get_u32_col(&batch, "start_line", i)?;
                 let end_line = get_u32_col(&batch, "end_line", i)?;
                 let score = get_f32_col(&batch, "_distance", i).unwrap_or(0.0);
                 let summary = get_nullable_str_col(&batch, "summary", i)?;
+                let references = get_str_list_col(&batch, "references", i)?;
+                let doc_links = get_str_list_col(&batch, "doc_links", i)?;
 
                 results.push(SearchResult {
                     id,
                     file_path,
+                    language,
                     start_line,
                     end_line,
                     symbol,
+                    qualified_symbol,
+                    parent_symbol,
+                    kind,
+                    visibility,
+                    signature,
                     content,
                     score,
                     summary,
+                    references,
+                    doc_links,
+                    vector: None,
                 });
             }
         }
 
         Ok(results)
     }
+
+    /// Rank by a weighted blend of code-vector and summary-vector similarity:
+    /// `score = alpha * sim(code) + (1 - alpha) * sim(summary)`.
+    ///
+    /// Both `vector` and `summary_vector` are searched (over-fetching
+    /// `limit * 4` from each so reranking has enough to work with), and the
+    /// summary scores are joined onto the code candidates by chunk id. Only
+    /// code-search candidates are returned: a chunk found by the summary
+    /// search alone is dropped. A code candidate with no summary-search hit
+    /// (no summary embedding, or outside the summary top candidates) keeps
+    /// its code-only score.
+    pub async fn search_blended(
+        &self,
+        vector: &[f32],
+        limit: usize,
+        alpha: f32,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SearchResult>> {
+        let candidates = limit.saturating_mul(4).max(limit);
+        let (code_results, summary_results) = tokio::join!(
+            self.search(vector, candidates, filters),
+            self.search_by_summary(vector, candidates, filters)
+        );
+        let (code_results, summary_results) = (code_results?, summary_results?);
+
+        let mut summary_scores: std::collections::HashMap<String, f32> = std::collections::HashMap::new();
+        for r in &summary_results {
+            summary_scores.insert(r.id.clone(), r.score);
+        }
+
+        let mut blended: Vec<SearchResult> = code_results
+            .into_iter()
+            .map(|mut r| {
+                r.score = match summary_scores.get(&r.id) {
+                    Some(&summary_score) => alpha * r.score + (1.0 - alpha) * summary_score,
+                    None => r.score,
+                };
+                r
+            })
+            .collect();
+
+        blended.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal));
+        blended.truncate(limit);
+        Ok(blended)
+    }
+}
+
+/// Lowercase, punctuation-splitting tokenizer shared by [`Store::full_text_search`].
+/// Underscores are kept as word characters so `parse_config` tokenizes as one
+/// term rather than splitting into `parse` and `config`.
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
 }
 
 fn get_str_col(batch: &RecordBatch, name: &str, row: usize) -> Result<String> {
@@ -356,6 +860,22 @@ fn get_nullable_str_col(
     }
 }
 
+fn get_str_list_col(batch: &RecordBatch, name: &str, row: usize) -> Result<Vec<String>> {
+    let col = batch
+        .column_by_name(name)
+        .ok_or_else(|| AppError::Other(anyhow::anyhow!("missing column: {}", name)))?;
+    let arr = col
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .ok_or_else(|| AppError::Other(anyhow::anyhow!("column {} is not ListArray", name)))?;
+    let values = arr.value(row);
+    let strs = values
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| AppError::Other(anyhow::anyhow!("column {} values are not StringArray", name)))?;
+    Ok((0..strs.len()).map(|i| strs.value(i).to_string()).collect())
+}
+
 fn get_u32_col(batch: &RecordBatch, name: &str, row: usize) -> Result<u32> {
     let col = batch
         .column_by_name(name)
@@ -377,3 +897,19 @@ fn get_f32_col(batch: &RecordBatch, name: &str, row: usize) -> Result<f32> {
         .ok_or_else(|| AppError::Other(anyhow::anyhow!("column {} is not Float32Array", name)))?;
     Ok(arr.value(row))
 }
+
+fn get_vector_col(batch: &RecordBatch, name: &str, row: usize) -> Result<Vec<f32>> {
+    let col = batch
+        .column_by_name(name)
+        .ok_or_else(|| AppError::Other(anyhow::anyhow!("missing column: {}", name)))?;
+    let arr = col
+        .as_any()
+        .downcast_ref::<FixedSizeListArray>()
+        .ok_or_else(|| AppError::Other(anyhow::anyhow!("column {} is not FixedSizeListArray", name)))?;
+    let values = arr.value(row);
+    let floats = values
+        .as_any()
+        .downcast_ref::<Float32Array>()
+        .ok_or_else(|| AppError::Other(anyhow::anyhow!("column {} values are not Float32Array", name)))?;
+    Ok(floats.values().to_vec())
+}
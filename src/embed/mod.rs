@@ -0,0 +1,169 @@
+pub mod nomic;
+pub mod ollama;
+pub mod unixcoder;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::config::AppConfig;
+use crate::error::{AppError, Result};
+use nomic::NomicEmbedder;
+use ollama::OllamaEmbedder;
+use unixcoder::UniXcoderEmbedder;
+
+/// Common interface over the embedding backends this crate supports.
+///
+/// Implementations distinguish code vs. query embedding because the prompt
+/// prefix required for good retrieval differs per model (e.g. CodeRankEmbed's
+/// "Represent this query…" instruction), while some models (UniXcoder) treat
+/// both the same way.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Embed a source-code chunk for indexing.
+    async fn embed_code(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Embed a natural-language query for retrieval.
+    async fn embed_query(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// The dimensionality of vectors this embedder produces.
+    fn dimension(&self) -> usize;
+
+    /// Estimate how many tokens `text` would consume, for token-budget-aware
+    /// batching. Falls back to a rough chars-per-token heuristic; embedders
+    /// with a tokenizer on hand (e.g. `NomicEmbedder`) should override this
+    /// with an exact count.
+    fn estimate_tokens(&self, text: &str) -> usize {
+        text.len().div_ceil(4).max(1)
+    }
+
+    /// Embed many code chunks in as few backend round-trips as possible.
+    /// The default simply calls `embed_code` one at a time; embedders whose
+    /// backend supports a native batch request (e.g. Ollama's
+    /// `GenerateEmbeddingsRequest`) should override this.
+    async fn embed_code_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut out = Vec::with_capacity(texts.len());
+        for text in texts {
+            out.push(self.embed_code(text).await?);
+        }
+        Ok(out)
+    }
+}
+
+#[async_trait]
+impl Embedder for NomicEmbedder {
+    async fn embed_code(&self, text: &str) -> Result<Vec<f32>> {
+        NomicEmbedder::embed_code(self, text).map_err(|e| AppError::Embed(e.to_string()))
+    }
+
+    async fn embed_query(&self, text: &str) -> Result<Vec<f32>> {
+        NomicEmbedder::embed_query(self, text).map_err(|e| AppError::Embed(e.to_string()))
+    }
+
+    fn dimension(&self) -> usize {
+        self.dim
+    }
+
+    fn estimate_tokens(&self, text: &str) -> usize {
+        self.token_count(text)
+    }
+}
+
+#[async_trait]
+impl Embedder for UniXcoderEmbedder {
+    // UniXcoder has no query-specific prompt prefix, so code and query share
+    // the same embedding path.
+    async fn embed_code(&self, text: &str) -> Result<Vec<f32>> {
+        UniXcoderEmbedder::embed(self, text).map_err(|e| AppError::Embed(e.to_string()))
+    }
+
+    async fn embed_query(&self, text: &str) -> Result<Vec<f32>> {
+        UniXcoderEmbedder::embed(self, text).map_err(|e| AppError::Embed(e.to_string()))
+    }
+
+    fn dimension(&self) -> usize {
+        self.dim
+    }
+
+    async fn embed_code_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        UniXcoderEmbedder::embed_batch(self, &texts).map_err(|e| AppError::Embed(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl Embedder for OllamaEmbedder {
+    // nomic-embed-text (the default Ollama model) has no query-specific
+    // instruction prefix either, so both paths call the same endpoint.
+    async fn embed_code(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed(text).await
+    }
+
+    async fn embed_query(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed(text).await
+    }
+
+    fn dimension(&self) -> usize {
+        self.dim
+    }
+
+    async fn embed_code_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.embed_batch(texts).await
+    }
+}
+
+/// Identifies the embedding model currently configured, for cache keying.
+/// Distinct providers (and UniXcoder variants) never share cache entries.
+pub fn model_id(config: &AppConfig) -> String {
+    match config.embed.provider.as_str() {
+        "unixcoder" => format!("unixcoder:{}", config.embed.unixcoder_variant),
+        other => other.to_string(),
+    }
+}
+
+/// Build the `Embedder` selected by `config.embed.provider`, loading its model
+/// weights on a blocking thread where needed.
+///
+/// Validates that the constructed embedder's dimensionality matches
+/// `config.db.embedding_dim` up front — a mismatch here is a config mistake
+/// the user should see immediately, rather than a confusing LanceDB schema
+/// error once the first batch of vectors is inserted.
+pub async fn build(config: &AppConfig) -> Result<Arc<dyn Embedder>> {
+    let embedder: Arc<dyn Embedder> = match config.embed.provider.as_str() {
+        "ollama" => Arc::new(OllamaEmbedder::new(
+            config.ollama.clone(),
+            config.db.embedding_dim,
+        )?),
+        "nomic" => {
+            let e = tokio::task::spawn_blocking(NomicEmbedder::load)
+                .await
+                .map_err(|e| AppError::Other(e.into()))?
+                .map_err(|e| AppError::Embed(e.to_string()))?;
+            Arc::new(e)
+        }
+        "unixcoder" => {
+            let variant = config.embed.unixcoder_variant.clone();
+            let e = tokio::task::spawn_blocking(move || UniXcoderEmbedder::load(&variant))
+                .await
+                .map_err(|e| AppError::Other(e.into()))?
+                .map_err(|e| AppError::Embed(e.to_string()))?;
+            Arc::new(e)
+        }
+        other => {
+            return Err(AppError::Embed(format!(
+                "unknown embedding provider '{other}' (expected ollama, unixcoder, or nomic)"
+            )));
+        }
+    };
+
+    if embedder.dimension() != config.db.embedding_dim {
+        return Err(AppError::Embed(format!(
+            "embedding_dim mismatch: config.db.embedding_dim = {}, but provider '{}' produces {}-dim vectors",
+            config.db.embedding_dim,
+            config.embed.provider,
+            embedder.dimension()
+        )));
+    }
+
+    Ok(embedder)
+}
@@ -70,6 +70,7 @@ pub struct NomicEmbedder {
     model: NomicBertModel,
     tokenizer: Tokenizer,
     device: Device,
+    pub(crate) dim: usize,
 }
 
 impl NomicEmbedder {
@@ -103,10 +104,17 @@ impl NomicEmbedder {
         let vb = unsafe {
             VarBuilder::from_mmaped_safetensors(&[&weights_path], DType::F32, &device)?
         };
+        let dim = config.hidden_size;
         let model = NomicBertModel::load(vb, &config)?;
 
         tracing::info!("  ready.");
-        Ok(Self { model, tokenizer, device })
+        Ok(Self { model, tokenizer, device, dim })
+    }
+
+    /// Number of tokens `text` would occupy under this model's tokenizer.
+    /// Used for token-budget-aware batching rather than a character/line estimate.
+    pub fn token_count(&self, text: &str) -> usize {
+        tokenize(&self.tokenizer, text).0.len()
     }
 
     /// Embed a code snippet. No prefix is prepended.
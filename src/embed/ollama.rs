@@ -1,18 +1,33 @@
-use ollama_rs::{generation::embeddings::request::GenerateEmbeddingsRequest, Ollama};
+use std::time::Duration;
+
+use ollama_rs::{
+    generation::embeddings::request::{EmbeddingsInput, GenerateEmbeddingsRequest},
+    Ollama,
+};
 
 use crate::config::OllamaConfig;
 use crate::error::{AppError, Result};
 
+/// Maximum number of retries for a batch embed call after a rate-limit
+/// response (HTTP 429) before giving up.
+const MAX_RETRIES: u32 = 5;
+/// Initial backoff delay; doubled after each retry.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
 pub struct OllamaEmbedder {
     client: Ollama,
     config: OllamaConfig,
+    pub(crate) dim: usize,
 }
 
 impl OllamaEmbedder {
-    pub fn new(config: OllamaConfig) -> Result<Self> {
+    /// `dim` is the embedding dimensionality expected from `config.embed_model`
+    /// (Ollama has no introspection endpoint for this, so it's supplied by the
+    /// caller — normally `config.db.embedding_dim`).
+    pub fn new(config: OllamaConfig, dim: usize) -> Result<Self> {
         let client = Ollama::try_new(&config.base_url)
             .map_err(|e| AppError::Embed(e.to_string()))?;
-        Ok(Self { client, config })
+        Ok(Self { client, config, dim })
     }
 
     pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
@@ -24,4 +39,45 @@ impl OllamaEmbedder {
         let vector: Vec<f32> = resp.embeddings[0].iter().map(|&x| x as f32).collect();
         Ok(vector)
     }
+
+    /// Embed many texts in a single Ollama round-trip, retrying the whole
+    /// batch with exponential backoff on an HTTP 429 rate-limit error. A
+    /// `retry-after` header is not read; the wait is always the backoff delay.
+    pub async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 0;
+        loop {
+            let req = GenerateEmbeddingsRequest::new(
+                self.config.embed_model.clone(),
+                EmbeddingsInput::Multiple(texts.to_vec()),
+            );
+            match self.client.generate_embeddings(req).await {
+                Ok(resp) => {
+                    let vectors = resp
+                        .embeddings
+                        .into_iter()
+                        .map(|v| v.into_iter().map(|x| x as f32).collect())
+                        .collect();
+                    return Ok(vectors);
+                }
+                Err(e) if is_rate_limited(&e) && attempt < MAX_RETRIES => {
+                    attempt += 1;
+                    tracing::warn!(
+                        "Ollama rate-limited embedding batch (attempt {attempt}/{MAX_RETRIES}), \
+                         retrying in {backoff:?}"
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => return Err(AppError::Embed(e.to_string())),
+            }
+        }
+    }
+}
+
+/// Best-effort detection of a 429 rate-limit response from ollama-rs's error
+/// type, which doesn't expose the HTTP status directly.
+fn is_rate_limited(err: &ollama_rs::error::OllamaError) -> bool {
+    let msg = err.to_string().to_ascii_lowercase();
+    msg.contains("429") || msg.contains("rate limit") || msg.contains("too many requests")
 }
@@ -158,6 +158,7 @@ pub struct UniXcoderEmbedder {
     model: UniXcoderModel,
     tokenizer: Tokenizer,
     device: Device,
+    pub(crate) dim: usize,
 }
 
 impl UniXcoderEmbedder {
@@ -209,10 +210,11 @@ impl UniXcoderEmbedder {
         } else {
             VarBuilder::from_pth(&weights_path, DTYPE, &device)?
         };
+        let dim = config.hidden_size;
         let model = UniXcoderModel::load(vb, &config)?;
 
         eprintln!("[maharajah]   ready.");
-        Ok(Self { model, tokenizer, device })
+        Ok(Self { model, tokenizer, device, dim })
     }
 
     /// Embed `text` using encoder-only mean pooling, returning an L2-normalised
@@ -235,4 +237,58 @@ impl UniXcoderEmbedder {
         let normalized = normalize_l2(&hidden)?;
         Ok(normalized.to_vec1::<f32>()?)
     }
+
+    /// Embed `texts` in a single batched forward pass, one L2-normalised
+    /// vector per input. Amortizes model invocation during indexing, where
+    /// hundreds of chunks are embedded back-to-back, instead of calling
+    /// `embed` once per chunk.
+    ///
+    /// Sequences shorter than the batch's longest are right-padded with the
+    /// `<pad>` id (1) and an accompanying attention mask is passed to `bert`
+    /// so pad positions contribute zero to self-attention; the same mask is
+    /// then used to mean-pool only the real tokens out of the hidden states,
+    /// so padding never contaminates the average. Synchronous; call from
+    /// `spawn_blocking`.
+    pub fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        const PAD_ID: u32 = 1;
+        let batch = texts.len();
+        let token_ids: Vec<Vec<u32>> = texts
+            .iter()
+            .map(|t| tokenize_encoder(&self.tokenizer, t, 512))
+            .collect();
+        let max_len = token_ids.iter().map(|ids| ids.len()).max().unwrap_or(0);
+
+        let mut input_flat = Vec::with_capacity(batch * max_len);
+        let mut mask_flat = Vec::with_capacity(batch * max_len);
+        for ids in &token_ids {
+            input_flat.extend(ids.iter().map(|&id| id as i64));
+            input_flat.extend(std::iter::repeat(PAD_ID as i64).take(max_len - ids.len()));
+            mask_flat.extend(std::iter::repeat(1f32).take(ids.len()));
+            mask_flat.extend(std::iter::repeat(0f32).take(max_len - ids.len()));
+        }
+
+        let input_ids = Tensor::from_vec(input_flat, (batch, max_len), &self.device)?;
+        let token_type_ids = Tensor::zeros((batch, max_len), DType::I64, &self.device)?;
+        let mask = Tensor::from_vec(mask_flat, (batch, max_len), &self.device)?;
+
+        // (batch, seq_len, hidden_size)
+        let hidden = self.model.bert.forward(&input_ids, &token_type_ids, Some(&mask))?;
+
+        let mask_expanded = mask.unsqueeze(2)?.broadcast_as(hidden.shape())?;
+        let summed = hidden.mul(&mask_expanded)?.sum(1)?; // (batch, hidden_size)
+        let counts = mask.sum(1)?.clamp(1f64, f64::INFINITY)?; // (batch,)
+        let counts = counts.unsqueeze(1)?.broadcast_as(summed.shape())?;
+        let mean = summed.div(&counts)?;
+
+        let mut out = Vec::with_capacity(batch);
+        for i in 0..batch {
+            let normalized = normalize_l2(&mean.get(i)?)?;
+            out.push(normalized.to_vec1::<f32>()?);
+        }
+        Ok(out)
+    }
 }
@@ -0,0 +1,201 @@
+//! Persistent, mmap-backed cache of parsed chunk lists, keyed by a file's
+//! relative path plus its content hash, so that re-running `index`/`refresh`
+//! over a large, mostly-unchanged tree can skip re-parsing every file and
+//! instead serve the previous run's `Vec<Chunk>` straight off disk.
+//!
+//! Physically a single append-only file under the database directory: each
+//! `put` appends a new `[key_len][key][value_len][value][crc32]` record, and
+//! an in-memory index of `key -> byte offset` is rebuilt by scanning the
+//! file once on open. The CRC32 covers the serialized value, so a record
+//! truncated or torn by a crash mid-write is detected on `get` (or during
+//! the startup scan) and treated as a miss rather than returned — the
+//! caller just re-parses and the cache heals itself on the next `put`.
+//! Overwriting a key leaves its old record in place and simply repoints the
+//! index at the new one; the file is meant to be deleted wholesale to
+//! reclaim space, not compacted in place.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+
+use crate::error::Result;
+use crate::indexer::parser::Chunk;
+
+/// File name for the chunk cache inside a `.maharajah/db` directory.
+const CACHE_FILE: &str = "chunk_cache.bin";
+
+/// Fast, non-cryptographic 64-bit digest of a file's path and contents, used
+/// to key chunk cache entries. Cheaper per-file than the SHA-256 digest
+/// `index_one_file` keeps for store-level staleness, and folding the source
+/// text in (not just the path) is what makes the cache correct: an edited
+/// file gets a new digest even if its path and mtime are unchanged, so the
+/// old entry is simply never looked up again rather than needing to be
+/// explicitly invalidated.
+pub fn file_digest(path: &Path, src: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    path.hash(&mut hasher);
+    src.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Build the cache key from a file's path relative to the target dir and its
+/// `file_digest`.
+pub fn cache_key(rel_path: &str, digest: u64) -> String {
+    format!("{rel_path}:{digest:016x}")
+}
+
+pub struct ChunkCache {
+    path: PathBuf,
+    index: HashMap<String, usize>,
+    mmap: Option<Mmap>,
+}
+
+impl ChunkCache {
+    /// Open the chunk cache file under `db_path`, creating an empty one if
+    /// it doesn't exist yet, and build its in-memory offset index.
+    pub fn open_or_create(db_path: &Path) -> Result<Self> {
+        std::fs::create_dir_all(db_path)?;
+        let path = db_path.join(CACHE_FILE);
+        if !path.exists() {
+            File::create(&path)?;
+        }
+
+        let mut cache = ChunkCache {
+            path,
+            index: HashMap::new(),
+            mmap: None,
+        };
+        cache.reload()?;
+        Ok(cache)
+    }
+
+    /// Rebuild the in-memory offset index by scanning the on-disk file.
+    /// A record whose declared length runs past the end of the file (a
+    /// truncated write from a crash mid-append) stops the scan rather than
+    /// misreading whatever garbage follows as the next record.
+    fn reload(&mut self) -> Result<()> {
+        let file = File::open(&self.path)?;
+        let mmap = if file.metadata()?.len() == 0 {
+            None
+        } else {
+            Some(unsafe { Mmap::map(&file)? })
+        };
+
+        let mut index = HashMap::new();
+        if let Some(map) = &mmap {
+            let mut offset = 0usize;
+            while let Some(record) = read_record(map, offset) {
+                if crc32(record.value) == record.crc {
+                    index.insert(record.key, offset);
+                }
+                offset += record.total_len;
+            }
+        }
+
+        self.index = index;
+        self.mmap = mmap;
+        Ok(())
+    }
+
+    /// Look up the chunk list cached for `key`. Returns `None` on a miss, a
+    /// checksum failure, or a deserialization failure — in every case the
+    /// caller should fall through to a full parse.
+    pub fn get(&self, key: &str) -> Option<Vec<Chunk>> {
+        let map = self.mmap.as_deref()?;
+        let offset = *self.index.get(key)?;
+        let record = read_record(map, offset)?;
+        if crc32(record.value) != record.crc {
+            return None;
+        }
+        serde_json::from_slice(record.value).ok()
+    }
+
+    /// Append a freshly parsed chunk list under `key`, making it visible to
+    /// future `get` calls (including on this same `ChunkCache` instance).
+    pub fn put(&mut self, key: &str, chunks: &[Chunk]) -> Result<()> {
+        let value = serde_json::to_vec(chunks).map_err(|e| crate::error::AppError::Other(e.into()))?;
+        let crc = crc32(&value);
+
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        let offset = file.metadata()?.len() as usize;
+        write_record(&mut file, key, &value, crc)?;
+        file.flush()?;
+
+        let file = File::open(&self.path)?;
+        self.mmap = Some(unsafe { Mmap::map(&file)? });
+        self.index.insert(key.to_string(), offset);
+        Ok(())
+    }
+
+    /// Look up `key`, falling back to `parse` (and caching its result) on a
+    /// miss — the single entry point the indexing pipeline should use
+    /// instead of calling `parser::parse_file_with_queries` directly.
+    pub fn get_or_parse(&mut self, key: &str, parse: impl FnOnce() -> Vec<Chunk>) -> Vec<Chunk> {
+        if let Some(chunks) = self.get(key) {
+            return chunks;
+        }
+        let chunks = parse();
+        if let Err(e) = self.put(key, &chunks) {
+            tracing::warn!("chunk cache: failed to write entry for {key}: {e}");
+        }
+        chunks
+    }
+}
+
+struct Record<'a> {
+    key: String,
+    value: &'a [u8],
+    crc: u32,
+    total_len: usize,
+}
+
+fn write_record(file: &mut File, key: &str, value: &[u8], crc: u32) -> Result<()> {
+    let key_bytes = key.as_bytes();
+    file.write_all(&(key_bytes.len() as u32).to_le_bytes())?;
+    file.write_all(key_bytes)?;
+    file.write_all(&(value.len() as u32).to_le_bytes())?;
+    file.write_all(value)?;
+    file.write_all(&crc.to_le_bytes())?;
+    Ok(())
+}
+
+/// Parse one `[key_len][key][value_len][value][crc32]` record at `offset`
+/// in `map`. Returns `None` if any field would read past the end of `map`.
+fn read_record(map: &[u8], offset: usize) -> Option<Record<'_>> {
+    let key_len = u32::from_le_bytes(map.get(offset..offset + 4)?.try_into().ok()?) as usize;
+    let key_start = offset + 4;
+    let key = std::str::from_utf8(map.get(key_start..key_start + key_len)?)
+        .ok()?
+        .to_string();
+
+    let value_len_start = key_start + key_len;
+    let value_len =
+        u32::from_le_bytes(map.get(value_len_start..value_len_start + 4)?.try_into().ok()?) as usize;
+    let value_start = value_len_start + 4;
+    let value = map.get(value_start..value_start + value_len)?;
+
+    let crc_start = value_start + value_len;
+    let crc = u32::from_le_bytes(map.get(crc_start..crc_start + 4)?.try_into().ok()?);
+
+    let total_len = 4 + key_len + 4 + value_len + 4;
+    Some(Record { key, value, crc, total_len })
+}
+
+/// IEEE CRC32 (the zlib/gzip polynomial) — simple enough to hand-roll rather
+/// than pull in a dedicated checksum crate for one use site.
+fn crc32(data: &[u8]) -> u32 {
+    const POLY: u32 = 0xEDB8_8320;
+    let mut crc: u32 = 0xFFFF_FFFF;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (POLY & mask);
+        }
+    }
+    !crc
+}
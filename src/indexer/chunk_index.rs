@@ -0,0 +1,269 @@
+//! Stable, zero-copy on-disk layout for a file's parsed chunks, distinct
+//! from [`cache::ChunkCache`](super::cache::ChunkCache)'s JSON-blob cache:
+//! where the cache exists to skip re-parsing, this index exists to let a
+//! reader mmap a prebuilt file and pull out a symbol name, summary, span,
+//! or language tag for one chunk without deserializing (or even paging in)
+//! the rest of the file.
+//!
+//! Layout, little-endian throughout:
+//!
+//! ```text
+//! [u32 version][u32 count]
+//! [count * u32 record_offset]     // absolute byte offset of each record
+//! record := [u8 lang_tag][u8 has_summary]
+//!           [u32 start_line][u32 end_line]
+//!           [u32 name_len][name bytes]
+//!           [u32 summary_len][summary bytes]   // only present if has_summary
+//! ```
+//!
+//! Only the fields a downstream tool actually needs to query without a full
+//! parse are stored (name, summary, span, language) — not the chunk's
+//! content, signature, or references. `record_offset` is looked up from the
+//! header. `open` accepts only a file whose `version` equals [`VERSION`]; a
+//! file written with any other version is rejected rather than read.
+
+#[cfg(test)]
+#[path = "chunk_index_tests.rs"]
+mod chunk_index_tests;
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::error::{AppError, Result};
+use crate::indexer::parser::Chunk;
+
+const VERSION: u32 = 1;
+
+/// The fixed set of languages `parser::grammar_for_ext` knows how to parse,
+/// encoded as a single byte per chunk instead of repeating the language
+/// name as a string in every record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LangTag {
+    Rust,
+    Python,
+    Java,
+    CSharp,
+    Scala,
+    Haskell,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+    Ruby,
+    FSharp,
+    /// A `language` string that doesn't match any known grammar name — e.g.
+    /// one produced by a user-supplied query for a language added after
+    /// this index's reader was built.
+    Unknown,
+}
+
+impl LangTag {
+    fn from_name(name: &str) -> LangTag {
+        match name {
+            "rust" => LangTag::Rust,
+            "python" => LangTag::Python,
+            "java" => LangTag::Java,
+            "csharp" => LangTag::CSharp,
+            "scala" => LangTag::Scala,
+            "haskell" => LangTag::Haskell,
+            "javascript" => LangTag::JavaScript,
+            "typescript" => LangTag::TypeScript,
+            "tsx" => LangTag::Tsx,
+            "go" => LangTag::Go,
+            "ruby" => LangTag::Ruby,
+            "fsharp" => LangTag::FSharp,
+            _ => LangTag::Unknown,
+        }
+    }
+
+    fn from_tag(tag: u8) -> LangTag {
+        match tag {
+            0 => LangTag::Rust,
+            1 => LangTag::Python,
+            2 => LangTag::Java,
+            3 => LangTag::CSharp,
+            4 => LangTag::Scala,
+            5 => LangTag::Haskell,
+            6 => LangTag::JavaScript,
+            7 => LangTag::TypeScript,
+            8 => LangTag::Tsx,
+            9 => LangTag::Go,
+            10 => LangTag::Ruby,
+            11 => LangTag::FSharp,
+            _ => LangTag::Unknown,
+        }
+    }
+
+    fn as_tag(self) -> u8 {
+        match self {
+            LangTag::Rust => 0,
+            LangTag::Python => 1,
+            LangTag::Java => 2,
+            LangTag::CSharp => 3,
+            LangTag::Scala => 4,
+            LangTag::Haskell => 5,
+            LangTag::JavaScript => 6,
+            LangTag::TypeScript => 7,
+            LangTag::Tsx => 8,
+            LangTag::Go => 9,
+            LangTag::Ruby => 10,
+            LangTag::FSharp => 11,
+            LangTag::Unknown => 255,
+        }
+    }
+}
+
+/// Serialize `chunks` to the zero-copy index layout at `path`, overwriting
+/// any existing file.
+pub fn write_index(path: &Path, chunks: &[Chunk]) -> Result<()> {
+    let mut records = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let mut record = Vec::new();
+        record.push(LangTag::from_name(&chunk.language).as_tag());
+        record.push(chunk.summary.is_some() as u8);
+        record.extend_from_slice(&chunk.start_line.to_le_bytes());
+        record.extend_from_slice(&chunk.end_line.to_le_bytes());
+
+        let name_bytes = chunk.symbol.as_bytes();
+        record.extend_from_slice(&(name_bytes.len() as u32).to_le_bytes());
+        record.extend_from_slice(name_bytes);
+
+        if let Some(summary) = &chunk.summary {
+            let summary_bytes = summary.as_bytes();
+            record.extend_from_slice(&(summary_bytes.len() as u32).to_le_bytes());
+            record.extend_from_slice(summary_bytes);
+        }
+
+        records.push(record);
+    }
+
+    let header_len = 4 + 4 + records.len() * 4;
+    let mut offsets = Vec::with_capacity(records.len());
+    let mut offset = header_len;
+    for record in &records {
+        offsets.push(offset as u32);
+        offset += record.len();
+    }
+
+    let mut file = File::create(path)?;
+    file.write_all(&VERSION.to_le_bytes())?;
+    file.write_all(&(records.len() as u32).to_le_bytes())?;
+    for o in &offsets {
+        file.write_all(&o.to_le_bytes())?;
+    }
+    for record in &records {
+        file.write_all(record)?;
+    }
+    Ok(())
+}
+
+/// A memory-mapped chunk index, opened read-only. `get` returns a
+/// [`ChunkView`] backed directly by the mapped bytes: `get` reads that one
+/// record's fields, borrowing its name and summary from the map rather than
+/// copying them, and no other record is read.
+pub struct ChunkIndexView {
+    mmap: Mmap,
+    offsets: Vec<u32>,
+}
+
+impl ChunkIndexView {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let version = read_u32(&mmap, 0).ok_or_else(|| truncated(path))?;
+        if version != VERSION {
+            return Err(AppError::Other(anyhow::anyhow!(
+                "{}: unsupported chunk index version {version} (expected {VERSION})",
+                path.display()
+            )));
+        }
+        let count = read_u32(&mmap, 4).ok_or_else(|| truncated(path))? as usize;
+
+        let mut offsets = Vec::with_capacity(count);
+        for i in 0..count {
+            offsets.push(read_u32(&mmap, 8 + i * 4).ok_or_else(|| truncated(path))?);
+        }
+
+        Ok(ChunkIndexView { mmap, offsets })
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// The chunk at `index`, or `None` if out of range.
+    pub fn get(&self, index: usize) -> Option<ChunkView<'_>> {
+        let offset = *self.offsets.get(index)? as usize;
+        ChunkView::at(&self.mmap, offset)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ChunkView<'_>> {
+        (0..self.len()).filter_map(move |i| self.get(i))
+    }
+}
+
+/// A single chunk record, read directly out of the mapped index with no
+/// allocation — `symbol`/`summary` borrow straight from the map.
+pub struct ChunkView<'a> {
+    lang_tag: LangTag,
+    start_line: u32,
+    end_line: u32,
+    symbol: &'a str,
+    summary: Option<&'a str>,
+}
+
+impl<'a> ChunkView<'a> {
+    fn at(map: &'a [u8], offset: usize) -> Option<Self> {
+        let lang_tag = LangTag::from_tag(*map.get(offset)?);
+        let has_summary = *map.get(offset + 1)? != 0;
+        let start_line = read_u32(map, offset + 2)?;
+        let end_line = read_u32(map, offset + 6)?;
+
+        let name_len = read_u32(map, offset + 10)? as usize;
+        let name_start = offset + 14;
+        let symbol = std::str::from_utf8(map.get(name_start..name_start + name_len)?).ok()?;
+
+        let summary = if has_summary {
+            let summary_len_start = name_start + name_len;
+            let summary_len = read_u32(map, summary_len_start)? as usize;
+            let summary_start = summary_len_start + 4;
+            Some(std::str::from_utf8(map.get(summary_start..summary_start + summary_len)?).ok()?)
+        } else {
+            None
+        };
+
+        Some(ChunkView { lang_tag, start_line, end_line, symbol, summary })
+    }
+
+    pub fn language(&self) -> LangTag {
+        self.lang_tag
+    }
+
+    pub fn symbol(&self) -> &'a str {
+        self.symbol
+    }
+
+    pub fn summary(&self) -> Option<&'a str> {
+        self.summary
+    }
+
+    pub fn span(&self) -> (u32, u32) {
+        (self.start_line, self.end_line)
+    }
+}
+
+fn read_u32(map: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(map.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn truncated(path: &Path) -> AppError {
+    AppError::Other(anyhow::anyhow!("{}: truncated chunk index", path.display()))
+}
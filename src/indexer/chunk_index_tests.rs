@@ -0,0 +1,49 @@
+/// Round-trip tests for the zero-copy on-disk chunk index, built on top of
+/// `parser::parse_file` so the records being written are exactly what the
+/// indexing pipeline would produce.
+
+#[cfg(test)]
+mod chunk_index_tests {
+    use crate::indexer::chunk_index::{write_index, ChunkIndexView, LangTag};
+    use crate::indexer::parser::parse_file;
+
+    #[test]
+    fn round_trips_symbol_summary_span_and_language() {
+        let content = "/// Adds two numbers.\nfn add(a: i32, b: i32) -> i32 { a + b }\n";
+        let chunks = parse_file(std::path::Path::new("t.rs"), content, 40, 0, 100000);
+
+        let path = std::env::temp_dir().join(format!(
+            "maharajah-chunk-index-test-{}.bin",
+            std::process::id()
+        ));
+        write_index(&path, &chunks).unwrap();
+
+        let view = ChunkIndexView::open(&path).unwrap();
+        assert_eq!(view.len(), chunks.len());
+
+        let add = view.iter().find(|c| c.symbol() == "add").expect("add chunk present");
+        assert_eq!(add.language(), LangTag::Rust);
+        assert_eq!(add.summary(), Some("Adds two numbers."));
+        assert_eq!(add.span(), (chunks[0].start_line, chunks[0].end_line));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn chunks_without_a_summary_read_back_as_none() {
+        let content = "fn square(x: i32) -> i32 { x * x }\n";
+        let chunks = parse_file(std::path::Path::new("t.rs"), content, 40, 0, 100000);
+
+        let path = std::env::temp_dir().join(format!(
+            "maharajah-chunk-index-test-no-summary-{}.bin",
+            std::process::id()
+        ));
+        write_index(&path, &chunks).unwrap();
+
+        let view = ChunkIndexView::open(&path).unwrap();
+        let square = view.iter().find(|c| c.symbol() == "square").expect("square chunk present");
+        assert_eq!(square.summary(), None);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -1,13 +1,58 @@
 use crate::indexer::parser::Chunk;
 
-/// Split `content` into overlapping windows of at most `max_lines` lines.
-/// `start_offset` is the line number of the first line of `content` within the original file.
+/// Rough token-count estimate for `s`, used to keep a chunk under an
+/// embedding model's context window without pulling in a real BPE
+/// tokenizer. Counts whitespace-delimited words plus standalone punctuation
+/// (each treated as its own sub-word token, loosely mirroring how a BPE
+/// vocabulary tends to split identifiers and operators) — a deliberate
+/// overestimate is safer here than an underestimate, since the cost of
+/// under-filling a chunk is negligible but overflowing the model's window
+/// silently truncates it.
+pub fn estimate_tokens(s: &str) -> usize {
+    let mut count = 0;
+    for word in s.split_whitespace() {
+        let mut run_start = true;
+        for c in word.chars() {
+            if c.is_alphanumeric() || c == '_' {
+                if run_start {
+                    count += 1;
+                    run_start = false;
+                }
+            } else {
+                count += 1;
+                run_start = true;
+            }
+        }
+    }
+    count
+}
+
+/// Split `content` into overlapping windows of at most `max_lines` lines
+/// and `max_tokens` estimated tokens (see `estimate_tokens`), whichever is
+/// hit first. `start_offset` is the line number of the first line of
+/// `content` within the original file. `overlap_lines` is how many trailing
+/// lines of one window reappear at the head of the next, so a symbol split
+/// across a chunk boundary doesn't lose all of its local context at the seam.
+///
+/// Lines are packed greedily: a window keeps accumulating lines until the
+/// next one would push it over either budget, then the window is emitted and
+/// the next one starts `overlap_lines` lines before the previous window's
+/// end (so a full `max_lines` window advances by `max_lines - overlap_lines`),
+/// clamped to advance by at least one line, so `overlap_lines >= max_lines`
+/// can't loop forever. A single line that alone exceeds `max_tokens` is
+/// hard-split at whitespace (see `hard_split_line`); a run of text with no
+/// whitespace stays in one piece even when it alone exceeds `max_tokens`.
+#[allow(clippy::too_many_arguments)]
 pub fn split_by_lines(
     content: &str,
     symbol: &str,
     language: &str,
     start_offset: u32,
     max_lines: usize,
+    overlap_lines: usize,
+    max_tokens: usize,
+    node_kind: &str,
+    summary: Option<&str>,
 ) -> Vec<Chunk> {
     let lines: Vec<&str> = content.lines().collect();
     if lines.is_empty() {
@@ -18,22 +63,126 @@ pub fn split_by_lines(
     let mut offset = 0usize;
 
     while offset < lines.len() {
-        let end = (offset + max_lines).min(lines.len());
+        let mut end = offset + 1;
+        let mut tokens = estimate_tokens(lines[offset]);
+
+        // A single line over budget gets its own chunk regardless — hard
+        // splitting happens below once its content is known.
+        while end < lines.len() && end - offset < max_lines {
+            let next_tokens = estimate_tokens(lines[end]);
+            if tokens + next_tokens > max_tokens {
+                break;
+            }
+            tokens += next_tokens;
+            end += 1;
+        }
+
         let chunk_lines = &lines[offset..end];
         let chunk_content = chunk_lines.join("\n");
         let start_line = start_offset + offset as u32;
         let end_line = start_offset + end as u32 - 1;
 
-        chunks.push(Chunk {
-            language: language.to_string(),
-            symbol: symbol.to_string(),
-            content: chunk_content,
-            start_line,
-            end_line,
-        });
+        if tokens > max_tokens && end - offset == 1 {
+            // The one line alone overflows the budget — hard-split it on
+            // token boundaries instead of emitting an oversized chunk.
+            chunks.extend(hard_split_line(
+                chunk_content.as_str(),
+                symbol,
+                language,
+                start_line,
+                max_tokens,
+                node_kind,
+                summary,
+            ));
+        } else {
+            let token_count = estimate_tokens(&chunk_content);
+            chunks.push(Chunk {
+                language: language.to_string(),
+                symbol: symbol.to_string(),
+                qualified_symbol: symbol.to_string(),
+                parent_symbol: None,
+                content: chunk_content,
+                start_line,
+                end_line,
+                node_kind: node_kind.to_string(),
+                summary: summary.map(str::to_string),
+                kind: crate::indexer::parser::SymbolKind::Other,
+                signature: None,
+                visibility: crate::indexer::parser::Visibility::Unknown,
+                references: Vec::new(),
+                doc_links: Vec::new(),
+                token_count,
+            });
+        }
 
-        offset += max_lines;
+        // Once a window reaches the last line, any further window would lie
+        // entirely inside this one and only re-emit its tail.
+        if end == lines.len() {
+            break;
+        }
+        offset = end.saturating_sub(overlap_lines).max(offset + 1);
     }
 
     chunks
 }
+
+/// Hard-split one over-budget line at whitespace into pieces of at most
+/// `max_tokens` each — the fallback for a single long line (e.g. a minified
+/// blob) that `split_by_lines`'s normal line-level packing can't shrink any
+/// further. Words are never split, so a single word over `max_tokens`
+/// becomes a piece of its own that exceeds the budget.
+fn hard_split_line(
+    line: &str,
+    symbol: &str,
+    language: &str,
+    start_line: u32,
+    max_tokens: usize,
+    node_kind: &str,
+    summary: Option<&str>,
+) -> Vec<Chunk> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.is_empty() {
+        return vec![];
+    }
+
+    let mut out = Vec::new();
+    let mut piece: Vec<&str> = Vec::new();
+    let mut tokens = 0usize;
+
+    for word in words {
+        let word_tokens = estimate_tokens(word).max(1);
+        if !piece.is_empty() && tokens + word_tokens > max_tokens {
+            out.push(piece.join(" "));
+            piece = Vec::new();
+            tokens = 0;
+        }
+        piece.push(word);
+        tokens += word_tokens;
+    }
+    if !piece.is_empty() {
+        out.push(piece.join(" "));
+    }
+
+    out.into_iter()
+        .map(|content| {
+            let token_count = estimate_tokens(&content);
+            Chunk {
+                language: language.to_string(),
+                symbol: symbol.to_string(),
+                qualified_symbol: symbol.to_string(),
+                parent_symbol: None,
+                content,
+                start_line,
+                end_line: start_line,
+                node_kind: node_kind.to_string(),
+                summary: summary.map(str::to_string),
+                kind: crate::indexer::parser::SymbolKind::Other,
+                signature: None,
+                visibility: crate::indexer::parser::Visibility::Unknown,
+                references: Vec::new(),
+                doc_links: Vec::new(),
+                token_count,
+            }
+        })
+        .collect()
+}
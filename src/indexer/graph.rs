@@ -0,0 +1,110 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::indexer::parser::Chunk;
+
+#[cfg(test)]
+#[path = "graph_tests.rs"]
+mod graph_tests;
+
+/// One indexed chunk's identity and the raw (unresolved) names its body
+/// refers to (see `parser::Chunk::references`).
+pub struct ChunkRef {
+    pub symbol: String,
+    pub file_path: String,
+    pub references: Vec<String>,
+}
+
+impl ChunkRef {
+    /// Build a `ChunkRef` from a parsed `Chunk`, cloning its `references` —
+    /// the usual way to feed `CallGraph::build` from a file's freshly parsed
+    /// (or re-read) chunks without hand-copying fields at each call site.
+    pub fn from_chunk(chunk: &Chunk, file_path: impl Into<String>) -> ChunkRef {
+        ChunkRef {
+            symbol: chunk.symbol.clone(),
+            file_path: file_path.into(),
+            references: chunk.references.clone(),
+        }
+    }
+}
+
+/// Directed caller → callee call graph, resolved by name against the set of
+/// symbols produced across the indexed corpus.
+///
+/// Resolution is name-based with a simple scope heuristic: prefer a
+/// same-file symbol, then a symbol sharing the same qualifying scope (e.g.
+/// both live under `Stack::`), then fall back to the first globally matching
+/// symbol. Names that don't resolve to any known symbol are dropped rather
+/// than guessed at.
+#[derive(Default)]
+pub struct CallGraph {
+    /// caller qualified symbol -> callee qualified symbols
+    callees: HashMap<String, HashSet<String>>,
+    /// callee qualified symbol -> caller qualified symbols (the "who calls
+    /// this" / references index)
+    callers: HashMap<String, HashSet<String>>,
+}
+
+impl CallGraph {
+    /// Build the graph from every chunk's raw references, resolving each
+    /// against `chunks`' own symbols.
+    ///
+    /// Self-edges (a symbol referencing itself, e.g. direct recursion) are
+    /// dropped unless `allow_recursive` is set.
+    pub fn build(chunks: &[ChunkRef], allow_recursive: bool) -> CallGraph {
+        // References are extracted as bare identifiers (e.g. `push`, not
+        // `Stack::push`), so index candidates by their symbol's last
+        // qualified segment.
+        let mut by_name: HashMap<&str, Vec<&ChunkRef>> = HashMap::new();
+        for c in chunks {
+            let last = c.symbol.rsplit("::").next().unwrap_or(c.symbol.as_str());
+            by_name.entry(last).or_default().push(c);
+        }
+
+        let mut graph = CallGraph::default();
+        for c in chunks {
+            let scope = c.symbol.rsplit_once("::").map(|(scope, _)| scope);
+            for reference in &c.references {
+                let Some(candidates) = by_name.get(reference.as_str()) else { continue };
+                let Some(target) = resolve(c, scope, candidates) else { continue };
+                if target.symbol == c.symbol && !allow_recursive {
+                    continue;
+                }
+                graph.callees.entry(c.symbol.clone()).or_default().insert(target.symbol.clone());
+                graph.callers.entry(target.symbol.clone()).or_default().insert(c.symbol.clone());
+            }
+        }
+        graph
+    }
+
+    /// Symbols called from `symbol` ("find callees").
+    pub fn callees(&self, symbol: &str) -> Vec<&str> {
+        self.callees
+            .get(symbol)
+            .map(|s| s.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Symbols that call `symbol` ("find callers" / references).
+    pub fn callers(&self, symbol: &str) -> Vec<&str> {
+        self.callers
+            .get(symbol)
+            .map(|s| s.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Pick the best-matching candidate for a reference made from `from`: a
+/// same-file symbol first, then one sharing `from`'s enclosing scope, then
+/// the first global match (deterministic by symbol name, since chunk order
+/// isn't meaningful across files).
+fn resolve<'a>(from: &ChunkRef, from_scope: Option<&str>, candidates: &[&'a ChunkRef]) -> Option<&'a ChunkRef> {
+    if let Some(&c) = candidates.iter().find(|c| c.file_path == from.file_path) {
+        return Some(c);
+    }
+    if let Some(scope) = from_scope {
+        if let Some(&c) = candidates.iter().find(|c| c.symbol != from.symbol && c.symbol.starts_with(scope)) {
+            return Some(c);
+        }
+    }
+    candidates.iter().min_by_key(|c| &c.symbol).copied()
+}
@@ -0,0 +1,82 @@
+/// Call-graph resolution tests, built on top of `parser::parse_file` so the
+/// references being resolved are exactly what the indexing pipeline would
+/// produce.
+
+#[cfg(test)]
+mod graph_tests {
+    use crate::indexer::graph::{CallGraph, ChunkRef};
+    use crate::indexer::parser::parse_file;
+    use std::path::Path;
+
+    fn chunk_refs(path: &str, content: &str) -> Vec<ChunkRef> {
+        parse_file(Path::new(path), content, 40, 0, 100000)
+            .into_iter()
+            .map(|c| ChunkRef::from_chunk(&c, path))
+            .collect()
+    }
+
+    #[test]
+    fn drops_self_edges_from_recursion_by_default() {
+        let content = include_str!("../../example/math.rs");
+        let refs = chunk_refs("math.rs", content);
+
+        // `factorial` calls itself recursively.
+        let graph = CallGraph::build(&refs, false);
+        assert!(
+            graph.callers("factorial").is_empty(),
+            "direct recursion should not produce a self-edge by default"
+        );
+        assert!(graph.callees("factorial").is_empty());
+
+        let recursive = CallGraph::build(&refs, true);
+        assert_eq!(recursive.callers("factorial"), vec!["factorial"]);
+        assert_eq!(recursive.callees("factorial"), vec!["factorial"]);
+    }
+
+    #[test]
+    fn drops_unresolved_references() {
+        // `run` calls `External::parse`, which isn't a symbol anywhere in
+        // this corpus — the reference must be dropped, not guessed at.
+        let src = concat!(
+            "fn run() -> i32 { let v = External::parse(\"x\"); v }\n",
+            "fn add(a: i32, b: i32) -> i32 { a + b }\n",
+        );
+        let refs = chunk_refs("t.rs", src);
+
+        let graph = CallGraph::build(&refs, false);
+        assert!(graph.callees("add").is_empty(), "`add` doesn't call anything");
+        assert!(graph.callees("run").is_empty(), "unresolved callee must not appear as an edge");
+    }
+
+    #[test]
+    fn prefers_same_file_over_global_match() {
+        // Two files each define a `helper` — a caller in file `a.rs` should
+        // resolve `helper()` to the `helper` living in the same file, not the
+        // one in `b.rs`.
+        let a = concat!(
+            "fn helper() -> i32 { 1 }\n",
+            "fn uses_helper() -> i32 { helper() }\n",
+        );
+        let b = "fn helper() -> i32 { 2 }\n";
+
+        let mut refs = chunk_refs("a.rs", a);
+        refs.extend(chunk_refs("b.rs", b));
+
+        let graph = CallGraph::build(&refs, false);
+        assert_eq!(graph.callees("uses_helper"), vec!["helper"]);
+        assert_eq!(graph.callers("helper"), vec!["uses_helper"]);
+    }
+
+    #[test]
+    fn resolves_calls_within_a_container() {
+        let content = include_str!("../../example/math.rs");
+        let refs = chunk_refs("math.rs", content);
+
+        // `Stack::push` is the only same-named symbol reachable from
+        // `Stack::push` itself (`self.data.push(value)` resolves by name to
+        // the method's own chunk) — a same-file match, correctly dropped as
+        // a self-edge rather than silently misattributed elsewhere.
+        let graph = CallGraph::build(&refs, false);
+        assert!(graph.callers("Stack::push").is_empty());
+    }
+}
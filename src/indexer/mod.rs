@@ -1,39 +1,72 @@
+pub mod cache;
+pub mod chunk_index;
 pub mod chunker;
+pub mod graph;
 pub mod parser;
+pub mod queue;
+pub mod triples;
 pub mod walker;
+pub mod watch;
 
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use sha2::{Digest, Sha256};
 
 use crate::cli::IndexArgs;
 use crate::config::AppConfig;
+use crate::db::cache::EmbeddingCache;
 use crate::db::store::{ChunkRecord, Store};
-use crate::embed::unixcoder::UniXcoderEmbedder;
+use crate::embed::{self, Embedder};
 use crate::error::{AppError, Result};
 
+/// Table name for the persistent per-chunk embedding cache, stored alongside
+/// the chunks table in the same LanceDB database.
+pub(crate) const EMBED_CACHE_TABLE: &str = "embedding_cache";
+
+/// Name of the heartbeat file `maharajah watch` maintains inside `db_path`
+/// while it's running. `refresh()` checks this before doing its own
+/// synchronous scan, since a live watch daemon is already keeping the index
+/// current in the background.
+const WATCH_MARKER_FILE: &str = "watch.heartbeat";
+
+/// How stale the watch daemon's heartbeat can be before `refresh()` stops
+/// trusting it (must be comfortably larger than `watch::HEARTBEAT_INTERVAL`).
+const WATCH_MARKER_TTL: Duration = Duration::from_secs(5);
+
+pub(crate) fn watch_marker_path(db_path: &Path) -> PathBuf {
+    db_path.join(WATCH_MARKER_FILE)
+}
+
+/// True if a `maharajah watch` daemon appears to be actively keeping this
+/// database fresh, i.e. its heartbeat file was touched within `WATCH_MARKER_TTL`.
+fn watch_daemon_active(db_path: &Path) -> bool {
+    std::fs::metadata(watch_marker_path(db_path))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.elapsed().ok())
+        .is_some_and(|age| age < WATCH_MARKER_TTL)
+}
+
 pub async fn run(
     config: &AppConfig,
     db_path: &Path,
     target_dir: &Path,
     args: IndexArgs,
 ) -> Result<()> {
-    let store = Store::open_or_create(
-        db_path,
+    let store = Store::from_addr(
+        &crate::config::resolve_store_addr(config, db_path),
         config.db.embedding_dim,
         &config.db.table_name,
         args.reindex,
     )
     .await?;
 
-    let variant = config.unixcoder.variant.clone();
-    let embedder = Arc::new(
-        tokio::task::spawn_blocking(move || UniXcoderEmbedder::load(&variant))
-            .await
-            .map_err(|e| AppError::Other(e.into()))?
-            .map_err(|e| AppError::Embed(e.to_string()))?,
-    );
+    let embedder = embed::build(config).await?;
+    let cache = EmbeddingCache::open_or_create(db_path, embedder.dimension(), EMBED_CACHE_TABLE)
+        .await?;
+    let mut chunk_cache = cache::ChunkCache::open_or_create(db_path)?;
 
     let mut exclude = args.exclude.clone();
     exclude.extend_from_slice(&config.index.default_excludes);
@@ -42,23 +75,60 @@ pub async fn run(
         &args.include,
         &exclude,
         &config.index.default_extensions,
+        config.index.respect_gitignore && !args.no_ignore,
     );
 
+    let query_dir = crate::config::resolve_query_dir(config);
     let total = files.len();
     let (indexed, skipped) = index_files(
         &store,
         embedder,
+        &cache,
+        config.cache.enabled,
+        &mut chunk_cache,
+        &embed::model_id(config),
         target_dir,
         &files,
         args.reindex,
         config.index.max_chunk_lines,
+        config.index.chunk_overlap_lines,
+        config.index.max_chunk_tokens,
+        config.index.max_embed_tokens,
+        Some(&query_dir),
     )
     .await?;
 
+    // Only purge on a full scan — an `--include`-scoped run only walked a
+    // subset of the tree, so absence from `files` wouldn't mean the file was
+    // actually deleted.
+    let removed = if args.include.is_empty() {
+        purge_deleted_files(&store, target_dir, &files).await?
+    } else {
+        0
+    };
+
     println!(
-        "Done. {total} files found: {indexed} indexed, {skipped} skipped (unchanged or binary)."
+        "Done. {total} files found: {indexed} indexed, {skipped} skipped (unchanged or binary), {removed} removed."
     );
 
+    // `--optimize` forces a rebuild regardless of row count; otherwise an ANN
+    // index is only worth the build cost once the table has grown enough that
+    // brute-force scan would start to hurt. Not wired into `refresh()` — that
+    // runs on every auto-refreshed find/query, where rebuilding an index on
+    // each call would cost far more than the scans it's meant to speed up.
+    if args.optimize {
+        store.create_vector_index("vector").await?;
+        if let Err(e) = store.create_vector_index("summary_vector").await {
+            tracing::warn!("skipping summary_vector ANN index: {e}");
+        }
+        println!("ANN index rebuilt on vector, summary_vector.");
+    } else if store.build_indexes_if_needed(config.db.ann_index_threshold).await? {
+        println!(
+            "Row count crossed {}: ANN index built on vector, summary_vector.",
+            config.db.ann_index_threshold
+        );
+    }
+
     Ok(())
 }
 
@@ -69,138 +139,286 @@ pub async fn refresh(
     db_path: &Path,
     target_dir: &Path,
 ) -> Result<(usize, usize)> {
-    let store = Store::open_or_create(
-        db_path,
+    // A `maharajah watch` daemon is already keeping this index fresh in the
+    // background — skip the synchronous scan and let `find`/`query` hit it
+    // as-is.
+    if watch_daemon_active(db_path) {
+        return Ok((0, 0));
+    }
+
+    let store = Store::from_addr(
+        &crate::config::resolve_store_addr(config, db_path),
         config.db.embedding_dim,
         &config.db.table_name,
         false,
     )
     .await?;
 
-    let variant = config.unixcoder.variant.clone();
-    let embedder = Arc::new(
-        tokio::task::spawn_blocking(move || UniXcoderEmbedder::load(&variant))
-            .await
-            .map_err(|e| AppError::Other(e.into()))?
-            .map_err(|e| AppError::Embed(e.to_string()))?,
-    );
+    let embedder = embed::build(config).await?;
+    let cache = EmbeddingCache::open_or_create(db_path, embedder.dimension(), EMBED_CACHE_TABLE)
+        .await?;
+    let mut chunk_cache = cache::ChunkCache::open_or_create(db_path)?;
 
     let files = walker::collect_files(
         target_dir,
         &[],
         &config.index.default_excludes,
         &config.index.default_extensions,
+        config.index.respect_gitignore,
     );
 
-    index_files(&store, embedder, target_dir, &files, false, config.index.max_chunk_lines).await
+    let query_dir = crate::config::resolve_query_dir(config);
+    let result = index_files(
+        &store,
+        embedder,
+        &cache,
+        config.cache.enabled,
+        &mut chunk_cache,
+        &embed::model_id(config),
+        target_dir,
+        &files,
+        false,
+        config.index.max_chunk_lines,
+        config.index.chunk_overlap_lines,
+        config.index.max_chunk_tokens,
+        config.index.max_embed_tokens,
+        Some(&query_dir),
+    )
+    .await?;
+
+    purge_deleted_files(&store, target_dir, &files).await?;
+
+    Ok(result)
+}
+
+/// Delete chunks for any file the `Store` has a row for but that the walk
+/// (`files`, already filtered by excludes/extensions/gitignore) no longer
+/// covers — i.e. the file was removed, renamed, or moved outside the
+/// indexed extensions since the last run. Returns the number of files purged.
+async fn purge_deleted_files(
+    store: &Store,
+    target_dir: &Path,
+    files: &[PathBuf],
+) -> Result<usize> {
+    let walked: std::collections::HashSet<String> = files
+        .iter()
+        .map(|p| {
+            p.strip_prefix(target_dir)
+                .unwrap_or(p)
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+
+    let mut removed = 0usize;
+    for stored_path in store.list_files().await? {
+        if !walked.contains(&stored_path) {
+            store.delete_file(&stored_path).await?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn index_files(
     store: &Store,
-    embedder: Arc<UniXcoderEmbedder>,
+    embedder: Arc<dyn Embedder>,
+    cache: &EmbeddingCache,
+    cache_enabled: bool,
+    chunk_cache: &mut cache::ChunkCache,
+    model_id: &str,
     target_dir: &Path,
     files: &[PathBuf],
     reindex: bool,
     max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+    max_embed_tokens: usize,
+    query_dir: Option<&Path>,
 ) -> Result<(usize, usize)> {
     let mut indexed = 0usize;
     let mut skipped = 0usize;
 
     for path in files {
-        let file_bytes = match std::fs::read(path) {
-            Ok(b) => b,
-            Err(e) => {
-                eprintln!("Warning: could not read {}: {}", path.display(), e);
-                continue;
-            }
-        };
+        if index_one_file(
+            store,
+            &*embedder,
+            cache,
+            cache_enabled,
+            chunk_cache,
+            model_id,
+            target_dir,
+            path,
+            reindex,
+            max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
+            max_embed_tokens,
+            query_dir,
+            None,
+        )
+        .await?
+        {
+            indexed += 1;
+        } else {
+            skipped += 1;
+        }
+    }
 
-        let current_hash = compute_hash(&file_bytes);
-
-        // Use path relative to target_dir as the stored key
-        let rel_path = path
-            .strip_prefix(target_dir)
-            .unwrap_or(path)
-            .to_string_lossy()
-            .into_owned();
-
-        if !reindex {
-            if let Some(stored_hash) = store.get_file_hash(&rel_path).await? {
-                if stored_hash == current_hash {
-                    skipped += 1;
-                    continue;
-                }
-                // Hash changed — remove stale chunks
-                store.delete_file(&rel_path).await?;
-            }
+    Ok((indexed, skipped))
+}
+
+/// (Re-)index a single file, skipping it if its content hash is unchanged
+/// from what's stored. Returns `true` if the file was (re-)indexed, `false`
+/// if it was skipped (unchanged, binary, unreadable, or produced no chunks).
+///
+/// Shared by the batch `index` command and the incremental `watch` daemon —
+/// both need the same read/hash/parse/embed/insert path for one file at a time.
+///
+/// `precomputed_chunks`, when given, is used instead of the usual
+/// `chunk_cache`-backed `parser::parse_file_with_queries` call — the
+/// `watch` daemon passes chunks it already produced via an
+/// `parser::IncrementalParser::reparse` edit, so a small save doesn't pay
+/// for a full tree-sitter walk of the whole file. They're still written
+/// into `chunk_cache` so a later `index`/`refresh` in a fresh process gets
+/// a cache hit instead of reparsing from scratch.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn index_one_file(
+    store: &Store,
+    embedder: &dyn Embedder,
+    cache: &EmbeddingCache,
+    cache_enabled: bool,
+    chunk_cache: &mut cache::ChunkCache,
+    model_id: &str,
+    target_dir: &Path,
+    path: &Path,
+    reindex: bool,
+    max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+    max_embed_tokens: usize,
+    query_dir: Option<&Path>,
+    precomputed_chunks: Option<Vec<parser::Chunk>>,
+) -> Result<bool> {
+    let file_bytes = match std::fs::read(path) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("Warning: could not read {}: {}", path.display(), e);
+            return Ok(false);
         }
+    };
 
-        let content = match String::from_utf8(file_bytes) {
-            Ok(s) => s,
-            Err(_) => {
-                // Skip binary files
-                skipped += 1;
-                continue;
+    let current_hash = compute_hash(&file_bytes);
+
+    // Use path relative to target_dir as the stored key
+    let rel_path = path
+        .strip_prefix(target_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned();
+
+    if !reindex {
+        if let Some(stored_hash) = store.get_file_hash(&rel_path).await? {
+            if stored_hash == current_hash {
+                return Ok(false);
             }
-        };
+            // Hash changed — remove stale chunks
+            store.delete_file(&rel_path).await?;
+        }
+    }
 
-        let chunks = parser::parse_file(path, &content, max_chunk_lines);
-        if chunks.is_empty() {
-            skipped += 1;
-            continue;
+    let content = match String::from_utf8(file_bytes) {
+        Ok(s) => s,
+        Err(_) => {
+            // Skip binary files
+            return Ok(false);
         }
+    };
 
-        // Embed all chunks for this file in one spawn_blocking call
-        let emb = Arc::clone(&embedder);
-        let contents: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
-        let summaries: Vec<Option<String>> = chunks.iter().map(|c| c.summary.clone()).collect();
-
-        let (vectors, summary_vectors): (Vec<Option<Vec<f32>>>, Vec<Option<Vec<f32>>>) =
-            tokio::task::spawn_blocking(move || {
-                let vecs: Vec<Option<Vec<f32>>> =
-                    contents.iter().map(|c| emb.embed(c).ok()).collect();
-                let svecs: Vec<Option<Vec<f32>>> = summaries
-                    .iter()
-                    .map(|s| s.as_deref().and_then(|text| emb.embed(text).ok()))
-                    .collect();
-                (vecs, svecs)
-            })
-            .await
-            .map_err(|e| AppError::Other(e.into()))?;
-
-        let mut records = Vec::with_capacity(chunks.len());
-        for ((chunk, vector_opt), summary_vector) in
-            chunks.into_iter().zip(vectors).zip(summary_vectors)
-        {
-            let vector = match vector_opt {
-                Some(v) => v,
-                None => {
-                    eprintln!("Warning: embed failed for {}", rel_path);
-                    continue;
-                }
-            };
-
-            records.push(ChunkRecord {
-                id: format!("{}:{}", rel_path, chunk.start_line),
-                file_path: rel_path.clone(),
-                file_hash: current_hash.clone(),
-                language: chunk.language,
-                symbol: chunk.symbol,
-                content: chunk.content,
-                start_line: chunk.start_line,
-                end_line: chunk.end_line,
-                vector,
-                summary: chunk.summary,
-                summary_vector,
-            });
+    let cache_key = cache::cache_key(&rel_path, cache::file_digest(path, &content));
+    let chunks = match precomputed_chunks {
+        Some(chunks) => {
+            let _ = chunk_cache.put(&cache_key, &chunks);
+            chunks
         }
+        None => chunk_cache.get_or_parse(&cache_key, || {
+            parser::parse_file_with_queries(
+                path,
+                &content,
+                max_chunk_lines,
+                overlap_lines,
+                max_chunk_tokens,
+                query_dir,
+            )
+        }),
+    };
+    if chunks.is_empty() {
+        return Ok(false);
+    }
 
-        store.insert(&records).await?;
-        tracing::info!("indexed: {rel_path} ({} chunks)", records.len());
-        indexed += 1;
+    // Embed this file's chunks in token-budgeted batches through the
+    // shared `Embedder`, consulting the content-addressed cache first so
+    // unchanged chunks never re-embed. Summaries are batched separately
+    // since only a subset of chunks carry one.
+    let contents: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+    let vectors =
+        queue::embed_batch_with_budget(embedder, cache, cache_enabled, model_id, &contents, max_embed_tokens)
+            .await;
+
+    let summary_idx: Vec<usize> = chunks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| c.summary.is_some().then_some(i))
+        .collect();
+    let summary_texts: Vec<String> = summary_idx
+        .iter()
+        .map(|&i| chunks[i].summary.clone().unwrap())
+        .collect();
+    let summary_results =
+        queue::embed_batch_with_budget(embedder, cache, cache_enabled, model_id, &summary_texts, max_embed_tokens)
+            .await;
+    let mut summary_vectors: Vec<Option<Vec<f32>>> = vec![None; chunks.len()];
+    for (&chunk_idx, vector) in summary_idx.iter().zip(summary_results) {
+        summary_vectors[chunk_idx] = vector;
     }
 
-    Ok((indexed, skipped))
+    let mut records = Vec::with_capacity(chunks.len());
+    for ((chunk, vector_opt), summary_vector) in chunks.into_iter().zip(vectors).zip(summary_vectors)
+    {
+        let vector = match vector_opt {
+            Some(v) => v,
+            None => {
+                eprintln!("Warning: embed failed for {}", rel_path);
+                continue;
+            }
+        };
+
+        records.push(ChunkRecord {
+            id: format!("{}:{}", rel_path, chunk.start_line),
+            file_path: rel_path.clone(),
+            file_hash: current_hash.clone(),
+            language: chunk.language,
+            symbol: chunk.symbol,
+            qualified_symbol: chunk.qualified_symbol,
+            parent_symbol: chunk.parent_symbol,
+            kind: chunk.kind.as_str().to_string(),
+            visibility: chunk.visibility.as_str().to_string(),
+            signature: chunk.signature,
+            content: chunk.content,
+            start_line: chunk.start_line,
+            end_line: chunk.end_line,
+            vector,
+            summary: chunk.summary,
+            references: chunk.references,
+            doc_links: chunk.doc_links,
+            summary_vector,
+        });
+    }
+
+    store.insert(&records).await?;
+    tracing::info!("indexed: {rel_path} ({} chunks)", records.len());
+    Ok(true)
 }
 
 fn compute_hash(data: &[u8]) -> String {
@@ -1,5 +1,7 @@
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use tree_sitter::{Language, Parser};
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Language, Parser, Query, QueryCursor};
 
 use crate::indexer::chunker;
 
@@ -8,9 +10,24 @@ use crate::indexer::chunker;
 mod parser_tests;
 
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Chunk {
     pub language: String,
     pub symbol: String,
+    /// `symbol`, explicitly — `symbol` is already emitted in fully-qualified
+    /// form (container chain joined by `qualify`, e.g. `Stack::push`), so
+    /// today the two are always equal. Kept as its own field so a caller
+    /// that specifically wants "the qualified path, whatever `symbol` means
+    /// in the future" (e.g. disambiguating two `new` methods on different
+    /// types) has a name to depend on that doesn't silently change meaning
+    /// if `symbol` is ever narrowed back to a bare leaf identifier.
+    pub qualified_symbol: String,
+    /// The enclosing container's `symbol` (e.g. `"Stack"` for a chunk whose
+    /// `symbol` is `"Stack::push"`), for a chunk emitted by recursing into a
+    /// container (see `container_kinds_for`), including a container nested
+    /// in another one. `None` for a top-level definition or a top-level
+    /// container chunk.
+    pub parent_symbol: Option<String>,
     pub content: String,
     pub start_line: u32,
     pub end_line: u32,
@@ -19,6 +36,98 @@ pub struct Chunk {
     pub node_kind: String,
     /// Extracted docstring or preceding comment block, if available
     pub summary: Option<String>,
+    /// Coarse-grained classification of the definition (function vs. type vs. …).
+    pub kind: SymbolKind,
+    /// The declaration head — name, params, return type — with the body cut
+    /// off, collapsed onto one line. `None` for chunks that aren't a single
+    /// definition (e.g. a line-split fallback chunk).
+    pub signature: Option<String>,
+    /// Best-effort visibility as declared in source (`pub`, `public`, a
+    /// leading underscore, …). `Unknown` where the language has no such
+    /// concept or none could be determined.
+    pub visibility: Visibility,
+    /// Bare names this chunk's body calls or constructs (e.g. `push`, not
+    /// `Stack::push` — callee qualification happens at resolution time, see
+    /// `indexer::graph`). Deduplicated and sorted; empty where the language
+    /// has no call-extraction support yet.
+    pub references: Vec<String>,
+    /// Symbols cross-referenced from `summary`'s text via a language's doc-
+    /// comment linking convention (Rust intra-doc links, Javadoc/Scaladoc
+    /// `{@link}`/`@see`, Python Sphinx roles, Haddock quoting) — see
+    /// `extract_doc_links`. Unresolved text, not yet matched against known
+    /// symbols; empty where the language has no such convention or `summary`
+    /// is `None`.
+    pub doc_links: Vec<String>,
+    /// Estimated token count of `content` (see `chunker::estimate_tokens`) —
+    /// lets the embedder assert a chunk never over-sends against a model's
+    /// context window before making the call.
+    pub token_count: usize,
+}
+
+/// Coarse classification of what a `Chunk` represents, modeled after the
+/// navigation-target kinds rust-analyzer surfaces for completion/goto-def.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SymbolKind {
+    Function,
+    /// A function defined inside a container (impl/class/object/module body).
+    Method,
+    Struct,
+    Class,
+    Enum,
+    Interface,
+    Trait,
+    TypeAlias,
+    Constant,
+    Module,
+    /// A recognized definition whose closest fit isn't one of the above
+    /// (e.g. a Rust `impl` block, a Haskell `instance`, an F# exception).
+    Other,
+}
+
+impl SymbolKind {
+    /// Lowercase name stored in `ChunkRecord::kind` (see `db::schema::chunks_schema`),
+    /// matching the lowercase convention already used for `Chunk::language`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Method => "method",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Class => "class",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Trait => "trait",
+            SymbolKind::TypeAlias => "type_alias",
+            SymbolKind::Constant => "constant",
+            SymbolKind::Module => "module",
+            SymbolKind::Other => "other",
+        }
+    }
+}
+
+/// Best-effort declared visibility of a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Visibility {
+    Public,
+    Private,
+    /// Package/module/assembly-internal (Java package-private, C# `internal`,
+    /// Rust `pub(crate)`, …).
+    Internal,
+    /// The language has no visibility modifiers, or none could be determined.
+    Unknown,
+}
+
+impl Visibility {
+    /// Lowercase name stored in `ChunkRecord::visibility` (see
+    /// `db::schema::chunks_schema`), matching the lowercase convention
+    /// already used for `Chunk::language`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Private => "private",
+            Visibility::Internal => "internal",
+            Visibility::Unknown => "unknown",
+        }
+    }
 }
 
 // ── per-language node kinds that represent meaningful top-level definitions ───
@@ -126,7 +235,13 @@ const FSHARP_KINDS: &[&str] = &[
 
 // ─────────────────────────────────────────────────────────────────────────────
 
-pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chunk> {
+pub fn parse_file(
+    path: &Path,
+    content: &str,
+    max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+) -> Vec<Chunk> {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -140,6 +255,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "rust",
             RUST_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "py" => parse_with_grammar(
             content,
@@ -147,6 +264,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "python",
             PYTHON_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "java" => parse_with_grammar(
             content,
@@ -154,6 +273,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "java",
             JAVA_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "cs" => parse_with_grammar(
             content,
@@ -161,6 +282,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "csharp",
             CSHARP_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "scala" | "sc" => parse_with_grammar(
             content,
@@ -168,6 +291,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "scala",
             SCALA_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "hs" => parse_with_grammar(
             content,
@@ -175,6 +300,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "haskell",
             HASKELL_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "js" | "cjs" | "mjs" | "jsx" => parse_with_grammar(
             content,
@@ -182,6 +309,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "javascript",
             JS_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "ts" => parse_with_grammar(
             content,
@@ -189,6 +318,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "typescript",
             TS_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "tsx" => parse_with_grammar(
             content,
@@ -196,6 +327,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "tsx",
             TS_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "go" => parse_with_grammar(
             content,
@@ -203,6 +336,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "go",
             GO_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "rb" => parse_with_grammar(
             content,
@@ -210,6 +345,8 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "ruby",
             RUBY_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         "fs" | "fsx" => parse_with_grammar(
             content,
@@ -217,43 +354,727 @@ pub fn parse_file(path: &Path, content: &str, max_chunk_lines: usize) -> Vec<Chu
             "fsharp",
             FSHARP_KINDS,
             max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
         ),
         _ => vec![],
     }
 }
 
-/// Generic tree-sitter parser: walks the AST and collects nodes whose kind is in
-/// `interesting_kinds`.  Falls back to line-based chunking if the tree could not
-/// be parsed or no interesting nodes were found.
-fn parse_with_grammar(
+/// The `(Language, lang_name, interesting_kinds)` triple `parse_file` would
+/// use for a given extension — factored out so `IncrementalParser::new` can
+/// pick the same grammar without duplicating the extension table. Returns
+/// `None` for an unsupported extension.
+fn grammar_for_ext(ext: &str) -> Option<(Language, &'static str, &'static [&'static str])> {
+    Some(match ext {
+        "rs" => (tree_sitter_rust::LANGUAGE.into(), "rust", RUST_KINDS),
+        "py" => (tree_sitter_python::LANGUAGE.into(), "python", PYTHON_KINDS),
+        "java" => (tree_sitter_java::LANGUAGE.into(), "java", JAVA_KINDS),
+        "cs" => (tree_sitter_c_sharp::LANGUAGE.into(), "csharp", CSHARP_KINDS),
+        "scala" | "sc" => (tree_sitter_scala::LANGUAGE.into(), "scala", SCALA_KINDS),
+        "hs" => (tree_sitter_haskell::LANGUAGE.into(), "haskell", HASKELL_KINDS),
+        "js" | "cjs" | "mjs" | "jsx" => (tree_sitter_javascript::LANGUAGE.into(), "javascript", JS_KINDS),
+        "ts" => (tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(), "typescript", TS_KINDS),
+        "tsx" => (tree_sitter_typescript::LANGUAGE_TSX.into(), "tsx", TS_KINDS),
+        "go" => (tree_sitter_go::LANGUAGE.into(), "go", GO_KINDS),
+        "rb" => (tree_sitter_ruby::LANGUAGE.into(), "ruby", RUBY_KINDS),
+        "fs" | "fsx" => (tree_sitter_fsharp::LANGUAGE_FSHARP.into(), "fsharp", FSHARP_KINDS),
+        _ => return None,
+    })
+}
+
+/// Like `parse_file`, but first checks `query_dir` for a user-supplied
+/// `<lang>.scm` tree-sitter query and, if one is present and produces at
+/// least one match, uses it in place of the crate's built-in per-language
+/// extraction. Falls back to `parse_file` when no `query_dir` is given, the
+/// language has no query file, or the query fails to compile or match
+/// anything — so a broken or overly narrow query degrades gracefully rather
+/// than silently dropping a file from the index.
+pub fn parse_file_with_queries(
+    path: &Path,
+    content: &str,
+    max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+    query_dir: Option<&Path>,
+) -> Vec<Chunk> {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+    if let Some(query_dir) = query_dir {
+        if let Some((language, lang_name, _)) = grammar_for_ext(ext) {
+            if let Some(query_source) = load_query_file(query_dir, lang_name) {
+                if let Some(chunks) = parse_with_query(
+                    content,
+                    language,
+                    lang_name,
+                    &query_source,
+                    max_chunk_lines,
+                    overlap_lines,
+                    max_chunk_tokens,
+                ) {
+                    return chunks;
+                }
+            }
+        }
+    }
+
+    parse_file(path, content, max_chunk_lines, overlap_lines, max_chunk_tokens)
+}
+
+/// Read `<query_dir>/<lang_name>.scm`, if it exists.
+fn load_query_file(query_dir: &Path, lang_name: &str) -> Option<String> {
+    std::fs::read_to_string(query_dir.join(format!("{lang_name}.scm"))).ok()
+}
+
+/// Run a user-supplied query against `content`, turning each match into a
+/// `Chunk`. The query must capture the definition node as `@symbol.def`;
+/// `@symbol.name` (falling back to `get_node_name`) supplies the symbol and
+/// `@doc`, if captured, becomes the chunk's summary. Returns `None` if the
+/// query fails to compile, declares no `@symbol.def` capture, or matches
+/// nothing, so the caller can fall back to the built-in extraction.
+#[allow(clippy::too_many_arguments)]
+fn parse_with_query(
     content: &str,
     language: Language,
     lang_name: &str,
-    interesting_kinds: &[&str],
+    query_source: &str,
     max_chunk_lines: usize,
-) -> Vec<Chunk> {
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+) -> Option<Vec<Chunk>> {
     let mut parser = Parser::new();
-    if parser.set_language(&language).is_err() {
-        return chunker::split_by_lines(content, "", lang_name, 0, max_chunk_lines, "", None);
-    }
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content, None)?;
 
-    let tree = match parser.parse(content, None) {
-        Some(t) => t,
-        None => return chunker::split_by_lines(content, "", lang_name, 0, max_chunk_lines, "", None),
-    };
+    let query = Query::new(&language, query_source).ok()?;
+    let def_idx = query.capture_index_for_name("symbol.def")?;
+    let name_idx = query.capture_index_for_name("symbol.name");
+    let doc_idx = query.capture_index_for_name("doc");
 
-    let root = tree.root_node();
+    let mut cursor = QueryCursor::new();
     let mut chunks = Vec::new();
-    let prune_kinds = prune_kinds_for(lang_name);
-    collect_chunks(root, content, lang_name, interesting_kinds, prune_kinds, max_chunk_lines, &mut chunks);
+    for m in cursor.matches(&query, tree.root_node(), content.as_bytes()) {
+        let Some(def_cap) = m.captures.iter().find(|c| c.index == def_idx) else {
+            continue;
+        };
+        let node = def_cap.node;
+        let node_content = &content[node.byte_range()];
+        let start_line = node.start_position().row as u32;
+        let end_line = node.end_position().row as u32;
+
+        let symbol = name_idx
+            .and_then(|idx| m.captures.iter().find(|c| c.index == idx))
+            .map(|c| content[c.node.byte_range()].to_string())
+            .unwrap_or_else(|| get_node_name(&node, content));
+        let summary = doc_idx
+            .and_then(|idx| m.captures.iter().find(|c| c.index == idx))
+            .map(|c| content[c.node.byte_range()].to_string());
+
+        if (end_line - start_line + 1) as usize > max_chunk_lines {
+            chunks.extend(chunker::split_by_lines(
+                node_content,
+                &symbol,
+                lang_name,
+                start_line,
+                max_chunk_lines,
+                overlap_lines,
+                max_chunk_tokens,
+                "",
+                None,
+            ));
+            continue;
+        }
+
+        let doc_links = summary.as_deref().map(|s| extract_doc_links(s, lang_name)).unwrap_or_default();
+
+        chunks.push(Chunk {
+            language: lang_name.to_string(),
+            symbol: symbol.clone(),
+            qualified_symbol: symbol.clone(),
+            parent_symbol: None,
+            content: node_content.to_string(),
+            start_line,
+            end_line,
+            node_kind: node.kind().to_string(),
+            summary,
+            kind: classify_kind(lang_name, node.kind(), false),
+            signature: extract_signature(node, content, lang_name),
+            visibility: extract_visibility(lang_name, node_content, &symbol),
+            references: extract_references(node, content, lang_name),
+            doc_links,
+            token_count: chunker::estimate_tokens(node_content),
+        });
+    }
 
     if chunks.is_empty() {
-        chunks = chunker::split_by_lines(content, "", lang_name, 0, max_chunk_lines, "", None);
+        None
+    } else {
+        Some(chunks)
+    }
+}
+
+/// Generic tree-sitter parser: walks the AST and collects nodes whose kind is in
+/// `interesting_kinds`.  Falls back to line-based chunking if the tree could not
+/// be parsed or no interesting nodes were found. A thin `collect()` over
+/// `ChunkIterator`, dropping any per-chunk `ParseError`s (a malformed region
+/// just yields fewer chunks, rather than failing the whole file).
+fn parse_with_grammar(
+    content: &str,
+    language: Language,
+    lang_name: &'static str,
+    interesting_kinds: &'static [&'static str],
+    max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+) -> Vec<Chunk> {
+    let mut chunks: Vec<Chunk> = match ChunkIterator::from_grammar(
+        content,
+        language,
+        lang_name,
+        interesting_kinds,
+        max_chunk_lines,
+        overlap_lines,
+        max_chunk_tokens,
+    ) {
+        Some(iter) => iter.filter_map(Result::ok).collect(),
+        None => vec![],
+    };
+
+    if chunks.is_empty() {
+        chunks = chunker::split_by_lines(
+            content,
+            "",
+            lang_name,
+            0,
+            max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
+            "",
+            None,
+        );
     }
 
     chunks
 }
 
+/// An error scoped to a single chunk — a node whose subtree tree-sitter
+/// flagged as containing a syntax error (see `Node::has_error`). The
+/// iterator still yields it rather than stopping, so one malformed region
+/// (e.g. a truncated function body from a mid-save read) doesn't cost the
+/// rest of the file's chunks.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub node_kind: &'static str,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "malformed {} node at lines {}-{} (contains a syntax error)",
+            self.node_kind, self.start_line, self.end_line
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Lazily walks a parsed tree and yields one `Chunk` (or `ParseError`) at a
+/// time, instead of `collect_chunks`' eager `Vec`, so a caller processing a
+/// huge source file can bound memory use and stop early (e.g. after the
+/// first N chunks over some size threshold) without paying for the whole
+/// file's chunk set up front.
+///
+/// Holds its own `Tree` and source text, and walks it via explicit
+/// child-index paths from the root rather than storing `Node`s across
+/// `next()` calls — a `Node`'s lifetime is tied to a borrow of the `Tree`,
+/// so storing one directly alongside the `Tree` it borrows from would make
+/// this struct self-referential. Re-deriving a node from its path is a few
+/// pointer-chasing `child()` calls, cheap relative to a tree-sitter parse.
+pub struct ChunkIterator {
+    tree: tree_sitter::Tree,
+    content: String,
+    lang_name: &'static str,
+    interesting_kinds: &'static [&'static str],
+    prune_kinds: &'static [&'static str],
+    max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+    // Work stack of (child-index path from root, enclosing scope). Popped
+    // depth-first; children are pushed in reverse so the leftmost is
+    // visited first, matching `collect_chunks`' traversal order.
+    stack: Vec<(Vec<usize>, Vec<String>)>,
+    // A node can yield more than one chunk (an oversized node falls back to
+    // several line-split chunks) — buffered here so `next()` can still
+    // return them one at a time.
+    pending: std::collections::VecDeque<Result<Chunk, ParseError>>,
+}
+
+impl ChunkIterator {
+    /// Build an iterator for `path`'s extension, resolving its tree-sitter
+    /// grammar the same way `parse_file` does. Returns `None` for an
+    /// unsupported extension or a source the grammar can't parse at all.
+    pub fn new(
+        path: &Path,
+        content: &str,
+        max_chunk_lines: usize,
+        overlap_lines: usize,
+        max_chunk_tokens: usize,
+    ) -> Option<Self> {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let (language, lang_name, interesting_kinds) = grammar_for_ext(ext)?;
+        Self::from_grammar(
+            content,
+            language,
+            lang_name,
+            interesting_kinds,
+            max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn from_grammar(
+        content: &str,
+        language: Language,
+        lang_name: &'static str,
+        interesting_kinds: &'static [&'static str],
+        max_chunk_lines: usize,
+        overlap_lines: usize,
+        max_chunk_tokens: usize,
+    ) -> Option<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(&language).ok()?;
+        let tree = parser.parse(content, None)?;
+
+        Some(ChunkIterator {
+            tree,
+            content: content.to_string(),
+            lang_name,
+            interesting_kinds,
+            prune_kinds: prune_kinds_for(lang_name),
+            max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
+            stack: vec![(vec![], vec![])],
+            pending: std::collections::VecDeque::new(),
+        })
+    }
+
+    fn node_at(&self, path: &[usize]) -> tree_sitter::Node<'_> {
+        let mut node = self.tree.root_node();
+        for &i in path {
+            node = node.child(i).expect("path was derived from this same tree");
+        }
+        node
+    }
+
+    /// Handle an interesting node: queue its chunk(s) (or a `ParseError` if
+    /// tree-sitter flagged it as malformed), then queue its container
+    /// children, if any, for traversal under its qualified scope.
+    ///
+    /// Everything read off the `Node` is copied into owned locals before any
+    /// of the `self.pending`/`self.stack` pushes below — `node_at` borrows
+    /// all of `self` for the `Node`'s lifetime, so holding it alive past the
+    /// first mutation of another field wouldn't satisfy the borrow checker.
+    fn visit_interesting(&mut self, path: &[usize], scope: &[String]) {
+        let node = self.node_at(path);
+        let has_error = node.has_error();
+        let start_line = node.start_position().row as u32;
+        let end_line = node.end_position().row as u32;
+        let node_kind = node.kind();
+        let node_content = self.content[node.byte_range()].to_string();
+        let local_name = definition_name(&node, &self.content);
+        let symbol = qualify(scope, &local_name);
+        let summary = if !has_error && is_summary_kind(self.lang_name, node_kind) {
+            extract_comment(node, &self.content, self.lang_name)
+        } else {
+            None
+        };
+        let references = extract_references(node, &self.content, self.lang_name);
+        let doc_links = summary.as_deref().map(|s| extract_doc_links(s, self.lang_name)).unwrap_or_default();
+        let child_count = node.child_count();
+        let is_container = container_kinds_for(self.lang_name).contains(&node_kind);
+
+        if has_error {
+            self.pending.push_back(Err(ParseError {
+                node_kind,
+                start_line,
+                end_line,
+            }));
+        } else if is_container {
+            // A container never gets line-split: its header is already
+            // small, and its members are chunked individually below.
+            let signature = extract_signature(node, &self.content, self.lang_name);
+            let content = signature.clone().unwrap_or_else(|| node_content.clone());
+            self.pending.push_back(Ok(Chunk {
+                language: self.lang_name.to_string(),
+                qualified_symbol: symbol.clone(),
+                parent_symbol: parent_symbol_for(scope),
+                symbol,
+                content,
+                start_line,
+                end_line,
+                node_kind: node_kind.to_string(),
+                summary,
+                kind: classify_kind(self.lang_name, node_kind, !scope.is_empty()),
+                signature,
+                visibility: extract_visibility(self.lang_name, &node_content, &local_name),
+                references,
+                doc_links: doc_links.clone(),
+                token_count: chunker::estimate_tokens(&content),
+            }));
+        } else {
+            let line_count = (end_line - start_line + 1) as usize;
+            if line_count > self.max_chunk_lines {
+                let sub = chunker::split_by_lines(
+                    &node_content,
+                    &symbol,
+                    self.lang_name,
+                    start_line,
+                    self.max_chunk_lines,
+                    self.overlap_lines,
+                    self.max_chunk_tokens,
+                    node_kind,
+                    summary.as_deref(),
+                );
+                self.pending.extend(sub.into_iter().map(Ok));
+            } else {
+                self.pending.push_back(Ok(Chunk {
+                    language: self.lang_name.to_string(),
+                    qualified_symbol: symbol.clone(),
+                    parent_symbol: parent_symbol_for(scope),
+                    symbol,
+                    content: node_content.clone(),
+                    start_line,
+                    end_line,
+                    node_kind: node_kind.to_string(),
+                    summary,
+                    kind: classify_kind(self.lang_name, node_kind, !scope.is_empty()),
+                    signature: extract_signature(node, &self.content, self.lang_name),
+                    visibility: extract_visibility(self.lang_name, &node_content, &local_name),
+                    references,
+                    doc_links,
+                    token_count: chunker::estimate_tokens(&node_content),
+                }));
+            }
+        }
+
+        if is_container {
+            let mut child_scope = scope.to_vec();
+            if !local_name.is_empty() {
+                child_scope.push(local_name);
+            }
+            self.push_children(path, child_count, &child_scope);
+        }
+    }
+
+    fn push_children(&mut self, path: &[usize], child_count: usize, scope: &[String]) {
+        for i in (0..child_count).rev() {
+            let mut child_path = path.to_vec();
+            child_path.push(i);
+            self.stack.push((child_path, scope.to_vec()));
+        }
+    }
+}
+
+impl Iterator for ChunkIterator {
+    type Item = Result<Chunk, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some(item);
+            }
+
+            let (path, scope) = self.stack.pop()?;
+            let node = self.node_at(&path);
+
+            if self.interesting_kinds.contains(&node.kind()) {
+                self.visit_interesting(&path, &scope);
+                continue;
+            }
+
+            if self.prune_kinds.contains(&node.kind()) {
+                continue;
+            }
+
+            let child_count = node.child_count();
+            self.push_children(&path, child_count, &scope);
+        }
+    }
+}
+
+/// Symbol-level diff between two successive `IncrementalParser::reparse` calls.
+#[derive(Debug, Default, Clone)]
+pub struct ChunkDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Present both before and after, but not reused wholesale — its
+    /// subtree overlapped an edited range.
+    pub changed: Vec<String>,
+}
+
+/// Stateful incremental parser for one file: keeps the previous
+/// `tree_sitter::Tree` plus the chunks it produced, so a small edit can be
+/// applied with `Parser::parse`'s `old_tree` argument instead of
+/// re-walking (and re-summarizing/re-signing) the whole file from scratch.
+///
+/// `IncrementalParser::new` returns `None` for an extension with no
+/// registered grammar; `parse_file` returns no chunks for such an extension
+/// either (its whole-file line-split fallback applies only to a file whose
+/// grammar exists but matches no definitions).
+pub struct IncrementalParser {
+    parser: Parser,
+    lang_name: &'static str,
+    interesting_kinds: &'static [&'static str],
+    max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+    tree: Option<tree_sitter::Tree>,
+    /// Chunks from the last parse, keyed by tree-sitter node id — reused
+    /// wholesale for nodes the next edit doesn't touch.
+    by_id: HashMap<usize, Chunk>,
+    /// Symbols from the last parse, for computing the next `ChunkDiff`.
+    last_symbols: HashSet<String>,
+}
+
+impl IncrementalParser {
+    pub fn new(path: &Path, max_chunk_lines: usize, overlap_lines: usize, max_chunk_tokens: usize) -> Option<Self> {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let (language, lang_name, interesting_kinds) = grammar_for_ext(ext)?;
+        let mut parser = Parser::new();
+        parser.set_language(&language).ok()?;
+        Some(IncrementalParser {
+            parser,
+            lang_name,
+            interesting_kinds,
+            max_chunk_lines,
+            overlap_lines,
+            max_chunk_tokens,
+            tree: None,
+            by_id: HashMap::new(),
+            last_symbols: HashSet::new(),
+        })
+    }
+
+    /// First parse of the file. Subsequent edits should go through `reparse`.
+    pub fn parse(&mut self, content: &str) -> Vec<Chunk> {
+        let Some(tree) = self.parser.parse(content, None) else {
+            self.tree = None;
+            self.by_id.clear();
+            self.last_symbols.clear();
+            return chunker::split_by_lines(
+                content,
+                "",
+                self.lang_name,
+                0,
+                self.max_chunk_lines,
+                self.overlap_lines,
+                self.max_chunk_tokens,
+                "",
+                None,
+            );
+        };
+
+        let mut chunks = Vec::new();
+        let mut by_id = HashMap::new();
+        let prune_kinds = prune_kinds_for(self.lang_name);
+        collect_chunks(
+            tree.root_node(),
+            content,
+            self.lang_name,
+            self.interesting_kinds,
+            prune_kinds,
+            self.max_chunk_lines,
+            self.overlap_lines,
+            self.max_chunk_tokens,
+            &[],
+            None,
+            &mut chunks,
+            &mut by_id,
+            &mut HashSet::new(),
+        );
+        if chunks.is_empty() {
+            chunks = chunker::split_by_lines(
+                content,
+                "",
+                self.lang_name,
+                0,
+                self.max_chunk_lines,
+                self.overlap_lines,
+                self.max_chunk_tokens,
+                "",
+                None,
+            );
+        }
+
+        self.last_symbols = chunks.iter().map(|c| c.symbol.clone()).collect();
+        self.by_id = by_id;
+        self.tree = Some(tree);
+        chunks
+    }
+
+    /// Apply `edits` to the tree from the previous `parse`/`reparse` call,
+    /// then re-emit chunks — reusing the cached chunk (summary, signature,
+    /// visibility, references included) for every node tree-sitter reports
+    /// as unaffected by the edit, and only re-extracting the rest.
+    ///
+    /// If there's no previous tree to edit (first call, or the grammar
+    /// failed to produce one last time), this is equivalent to `parse`.
+    pub fn reparse(
+        &mut self,
+        old_content: &str,
+        new_content: &str,
+        edits: &[tree_sitter::InputEdit],
+    ) -> (Vec<Chunk>, ChunkDiff) {
+        debug_assert!(
+            edits.iter().all(|e| e.old_end_byte <= old_content.len()),
+            "edit out of bounds for old_content"
+        );
+
+        let Some(mut old_tree) = self.tree.take() else {
+            let chunks = self.parse(new_content);
+            let diff = ChunkDiff { added: chunks.iter().map(|c| c.symbol.clone()).collect(), ..Default::default() };
+            return (chunks, diff);
+        };
+        for edit in edits {
+            old_tree.edit(edit);
+        }
+
+        let Some(new_tree) = self.parser.parse(new_content, Some(&old_tree)) else {
+            // Grammar failed on the edited content — fall back to a
+            // whole-file line split and report every previous symbol gone.
+            self.tree = None;
+            self.by_id.clear();
+            let chunks = chunker::split_by_lines(
+                new_content,
+                "",
+                self.lang_name,
+                0,
+                self.max_chunk_lines,
+                self.overlap_lines,
+                self.max_chunk_tokens,
+                "",
+                None,
+            );
+            let new_symbols: HashSet<String> = chunks.iter().map(|c| c.symbol.clone()).collect();
+            let diff = ChunkDiff {
+                removed: self.last_symbols.difference(&new_symbols).cloned().collect(),
+                added: new_symbols.difference(&self.last_symbols).cloned().collect(),
+                changed: Vec::new(),
+            };
+            self.last_symbols = new_symbols;
+            return (chunks, diff);
+        };
+
+        let changed_ranges: Vec<tree_sitter::Range> = old_tree.changed_ranges(&new_tree).collect();
+
+        let mut chunks = Vec::new();
+        let mut by_id = HashMap::new();
+        let mut reused_symbols = HashSet::new();
+        let prune_kinds = prune_kinds_for(self.lang_name);
+        collect_chunks(
+            new_tree.root_node(),
+            new_content,
+            self.lang_name,
+            self.interesting_kinds,
+            prune_kinds,
+            self.max_chunk_lines,
+            self.overlap_lines,
+            self.max_chunk_tokens,
+            &[],
+            Some((&changed_ranges, &self.by_id)),
+            &mut chunks,
+            &mut by_id,
+            &mut reused_symbols,
+        );
+        if chunks.is_empty() {
+            chunks = chunker::split_by_lines(
+                new_content,
+                "",
+                self.lang_name,
+                0,
+                self.max_chunk_lines,
+                self.overlap_lines,
+                self.max_chunk_tokens,
+                "",
+                None,
+            );
+        }
+
+        let new_symbols: HashSet<String> = chunks.iter().map(|c| c.symbol.clone()).collect();
+        let diff = ChunkDiff {
+            added: new_symbols.difference(&self.last_symbols).cloned().collect(),
+            removed: self.last_symbols.difference(&new_symbols).cloned().collect(),
+            changed: new_symbols
+                .intersection(&self.last_symbols)
+                .filter(|s| !reused_symbols.contains(s.as_str()))
+                .cloned()
+                .collect(),
+        };
+
+        self.last_symbols = new_symbols;
+        self.by_id = by_id;
+        self.tree = Some(new_tree);
+        (chunks, diff)
+    }
+
+    /// Like `reparse`, but computes `edits` itself from a plain byte diff of
+    /// `old_content` vs `new_content` (see `diff_edits`) instead of requiring
+    /// the caller to track positions — the usual entry point for a watch
+    /// daemon that only has two whole-file reads of the same path.
+    pub fn reparse_diff(&mut self, old_content: &str, new_content: &str) -> (Vec<Chunk>, ChunkDiff) {
+        let edits = diff_edits(old_content, new_content);
+        self.reparse(old_content, new_content, &edits)
+    }
+}
+
+/// Compute the `tree_sitter::InputEdit`s needed to turn `old_content` into
+/// `new_content`, as a single edit spanning the smallest byte range that
+/// covers every difference (common prefix and suffix trimmed off either
+/// side) — the same shape `Tree::edit`/`IncrementalParser::reparse` expect.
+/// Returns an empty `Vec` if the two strings are identical.
+pub(crate) fn diff_edits(old_content: &str, new_content: &str) -> Vec<tree_sitter::InputEdit> {
+    let old_bytes = old_content.as_bytes();
+    let new_bytes = new_content.as_bytes();
+
+    let prefix = old_bytes.iter().zip(new_bytes.iter()).take_while(|(a, b)| a == b).count();
+    if prefix == old_bytes.len() && prefix == new_bytes.len() {
+        return Vec::new();
+    }
+
+    let suffix = old_bytes[prefix..]
+        .iter()
+        .rev()
+        .zip(new_bytes[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_end_byte = old_bytes.len() - suffix;
+    let new_end_byte = new_bytes.len() - suffix;
+
+    vec![tree_sitter::InputEdit {
+        start_byte: prefix,
+        old_end_byte,
+        new_end_byte,
+        start_position: byte_to_point(old_content, prefix),
+        old_end_position: byte_to_point(old_content, old_end_byte),
+        new_end_position: byte_to_point(new_content, new_end_byte),
+    }]
+}
+
+/// The `tree_sitter::Point` (row, column) of a byte offset into `content`.
+fn byte_to_point(content: &str, byte_offset: usize) -> tree_sitter::Point {
+    let prefix = &content.as_bytes()[..byte_offset];
+    let row = prefix.iter().filter(|&&b| b == b'\n').count();
+    let col = byte_offset - prefix.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    tree_sitter::Point { row, column: col }
+}
+
 /// Node kinds that should never be recursed into during chunk collection.
 /// This prevents false-positive matches when a grammar reuses the same kind
 /// for structurally different constructs (e.g. Haskell uses `function` for
@@ -267,6 +1088,256 @@ fn prune_kinds_for(lang: &str) -> &'static [&'static str] {
     }
 }
 
+/// Node kinds that hold nested member definitions (methods, associated
+/// functions, …) worth chunking in their own right — a class/impl/object/
+/// trait/module body, not a leaf definition like a bare function.
+///
+/// Matching one of these still produces a chunk for the container itself,
+/// but as a lightweight header only — `Chunk::content` is just its
+/// signature, body elided, rather than the full (possibly huge) source
+/// range — and unlike a leaf definition, `collect_chunks` also descends
+/// into its body so nested members are chunked individually, with
+/// `Chunk::symbol` qualified by the chain of enclosing container names
+/// (e.g. `Stack::push`) and `Chunk::parent_symbol` pointing back at it.
+fn container_kinds_for(lang: &str) -> &'static [&'static str] {
+    match lang {
+        "rust" => &["impl_item", "trait_item", "mod_item"],
+        "python" => &["class_definition"],
+        "java" => &[
+            "class_declaration",
+            "interface_declaration",
+            "enum_declaration",
+            "record_declaration",
+            "annotation_type_declaration",
+        ],
+        "csharp" => &[
+            "class_declaration",
+            "interface_declaration",
+            "struct_declaration",
+            "enum_declaration",
+            "record_declaration",
+        ],
+        "scala" => &["class_definition", "object_definition", "trait_definition"],
+        "haskell" => &["class", "instance_decl"],
+        "javascript" => &["class_declaration"],
+        "typescript" | "tsx" => &["class_declaration"],
+        "ruby" => &["class", "module"],
+        "fsharp" => &["module_defn", "namespace"],
+        _ => &[],
+    }
+}
+
+/// Node kinds that represent a call or object-construction expression, per
+/// language — the raw material for `extract_references`. Languages with no
+/// entry here (Haskell, F#: application nodes are too unstructured to
+/// isolate a callee cheaply) simply yield no references.
+fn call_kinds_for(lang: &str) -> &'static [&'static str] {
+    match lang {
+        "rust" => &["call_expression", "macro_invocation"],
+        "python" => &["call"],
+        "java" => &["method_invocation", "object_creation_expression"],
+        "csharp" => &["invocation_expression", "object_creation_expression"],
+        "scala" => &["call_expression", "instance_expression"],
+        "javascript" => &["call_expression", "new_expression"],
+        "typescript" | "tsx" => &["call_expression", "new_expression"],
+        "go" => &["call_expression"],
+        "ruby" => &["call", "method_call"],
+        _ => &[],
+    }
+}
+
+/// Walk `node`'s subtree and collect the bare callee name of every call or
+/// construction expression found inside it (e.g. `self.stack.push(x)` yields
+/// `push`). Deduplicated and sorted for determinism.
+fn extract_references(node: tree_sitter::Node, content: &str, lang: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    collect_references(node, content, call_kinds_for(lang), &mut out);
+    out.sort();
+    out.dedup();
+    out
+}
+
+fn collect_references(node: tree_sitter::Node, content: &str, call_kinds: &[&str], out: &mut Vec<String>) {
+    if call_kinds.contains(&node.kind()) {
+        if let Some(callee) = node.named_child(0) {
+            if let Some(name) = last_identifier_in(callee, content) {
+                out.push(name);
+            }
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_references(child, content, call_kinds, out);
+    }
+}
+
+/// Scan a chunk's already-extracted `summary` text for that language's
+/// doc-comment cross-reference convention and return the target text of
+/// each link found — Rust intra-doc links, Javadoc/Scaladoc `{@link}`/
+/// `@see`, Python Sphinx roles, or Haddock quoting. Best-effort text
+/// scanning, not a doc-comment parser: targets aren't resolved against
+/// known symbols here (see `indexer::graph` for that, over
+/// `Chunk::references`). Empty for languages with no such convention.
+fn extract_doc_links(summary: &str, lang: &str) -> Vec<String> {
+    match lang {
+        "rust" => rust_doc_links(summary),
+        "java" | "scala" => javadoc_links(summary),
+        "python" => sphinx_roles(summary),
+        "haskell" => haddock_links(summary),
+        _ => Vec::new(),
+    }
+}
+
+/// Rust intra-doc links: `[Type]` or `` [`mod::item`] ``. A `[text](url)`
+/// markdown link is not a cross-reference and is skipped.
+fn rust_doc_links(summary: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut rest = summary;
+    while let Some(start) = rest.find('[') {
+        let after_bracket = &rest[start + 1..];
+        let Some(end) = after_bracket.find(']') else { break };
+        let inner = &after_bracket[..end];
+        let after_close = &after_bracket[end + 1..];
+        if !after_close.starts_with('(') {
+            let target = inner.trim().trim_matches('`');
+            if !target.is_empty() {
+                out.push(target.to_string());
+            }
+        }
+        rest = after_close;
+    }
+    out
+}
+
+/// Javadoc/Scaladoc cross-references: `{@link Target}` and `@see Target`
+/// (the first whitespace-delimited token after `@see`).
+fn javadoc_links(summary: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut rest = summary;
+    while let Some(start) = rest.find("{@link") {
+        let after = &rest[start + "{@link".len()..];
+        let Some(end) = after.find('}') else { break };
+        if let Some(target) = after[..end].split_whitespace().next() {
+            let target = target.split('#').next().unwrap_or(target);
+            if !target.is_empty() {
+                out.push(target.to_string());
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    for line in summary.lines() {
+        if let Some(after) = line.trim().strip_prefix("@see ") {
+            if let Some(target) = after.split_whitespace().next() {
+                out.push(target.to_string());
+            }
+        }
+    }
+    out
+}
+
+/// Python Sphinx cross-reference roles: `` :func:`target` `` and
+/// `` :class:`target` ``.
+fn sphinx_roles(summary: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for role in [":func:", ":class:"] {
+        let mut rest = summary;
+        while let Some(start) = rest.find(role) {
+            let after = &rest[start + role.len()..];
+            let Some(open) = after.find('`') else { break };
+            let after_tick = &after[open + 1..];
+            let Some(close) = after_tick.find('`') else { break };
+            let target = &after_tick[..close];
+            if !target.is_empty() {
+                out.push(target.to_string());
+            }
+            rest = &after_tick[close + 1..];
+        }
+    }
+    out
+}
+
+/// Haddock cross-reference quoting: `'Name'` for values/types, `"Module"`
+/// for module names.
+fn haddock_links(summary: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    haddock_quoted(summary, '\'', &mut out);
+    haddock_quoted(summary, '"', &mut out);
+    out
+}
+
+fn haddock_quoted(summary: &str, quote: char, out: &mut Vec<String>) {
+    let mut rest = summary;
+    while let Some(start) = rest.find(quote) {
+        let after = &rest[start + quote.len_utf8()..];
+        let Some(end) = after.find(quote) else { break };
+        let inner = &after[..end];
+        if inner.chars().next().is_some_and(|c| c.is_alphabetic()) && !inner.contains(char::is_whitespace) {
+            out.push(inner.to_string());
+        }
+        rest = &after[end + quote.len_utf8()..];
+    }
+}
+
+/// Find the rightmost identifier-like leaf in a subtree — for a call's
+/// callee expression this is the invoked name itself, even through a
+/// qualified access chain (`a.b.push` → `push`).
+fn last_identifier_in(node: tree_sitter::Node, content: &str) -> Option<String> {
+    if matches!(
+        node.kind(),
+        "identifier" | "field_identifier" | "property_identifier" | "type_identifier" | "constant" | "variable"
+    ) {
+        return Some(content[node.byte_range()].to_string());
+    }
+    let mut cursor = node.walk();
+    let mut last = None;
+    for child in node.children(&mut cursor) {
+        if let Some(name) = last_identifier_in(child, content) {
+            last = Some(name);
+        }
+    }
+    last
+}
+
+/// Join an enclosing-scope chain and a local name into a qualified symbol,
+/// e.g. `qualify(&["Stack".into()], "push") == "Stack::push"`.
+fn qualify(scope: &[String], name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else if name.is_empty() {
+        scope.join("::")
+    } else {
+        format!("{}::{}", scope.join("::"), name)
+    }
+}
+
+/// `Chunk::parent_symbol` for a chunk collected at `scope` — the immediately
+/// enclosing container's qualified symbol, or `None` at top level.
+fn parent_symbol_for(scope: &[String]) -> Option<String> {
+    (!scope.is_empty()).then(|| scope.join("::"))
+}
+
+/// `get_node_name`, except that a Rust `impl_item` is named after its `type`
+/// field (`impl<T> Stack<T>` → `Stack`, `impl Display for Foo` → `Foo`).
+fn definition_name(node: &tree_sitter::Node, content: &str) -> String {
+    if node.kind() == "impl_item" {
+        if let Some(ty) = node.child_by_field_name("type") {
+            return match ty.kind() {
+                "generic_type" => get_node_name(&ty, content),
+                _ => content[ty.byte_range()].to_string(),
+            };
+        }
+    }
+    get_node_name(node, content)
+}
+
+/// When driven by `IncrementalParser::reparse`, the ranges tree-sitter
+/// reports as changed since the last parse plus the previous parse's chunks
+/// (keyed by tree-sitter node id) — lets `collect_chunks` reuse a cached
+/// chunk wholesale for any node outside those ranges instead of
+/// re-summarizing/re-signing it.
+type IncrementalInput<'a> = (&'a [tree_sitter::Range], &'a HashMap<usize, Chunk>);
+
+#[allow(clippy::too_many_arguments)]
 fn collect_chunks(
     node: tree_sitter::Node,
     content: &str,
@@ -274,13 +1345,33 @@ fn collect_chunks(
     interesting_kinds: &[&str],
     prune_kinds: &[&str],
     max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+    scope: &[String],
+    incremental: Option<IncrementalInput>,
     chunks: &mut Vec<Chunk>,
+    by_id: &mut HashMap<usize, Chunk>,
+    reused_symbols: &mut HashSet<String>,
 ) {
     if interesting_kinds.contains(&node.kind()) {
         let start_line = node.start_position().row as u32;
         let end_line = node.end_position().row as u32;
+
+        if let Some(reused) = reuse_cached_chunk(&node, incremental, start_line, end_line) {
+            reused_symbols.insert(reused.symbol.clone());
+            by_id.insert(node.id(), reused.clone());
+            let local_name = reused.symbol.rsplit("::").next().unwrap_or(reused.symbol.as_str()).to_string();
+            chunks.push(reused);
+            recurse_into_container(
+                node, content, lang_name, interesting_kinds, prune_kinds, max_chunk_lines, overlap_lines,
+                max_chunk_tokens, scope, &local_name, incremental, chunks, by_id, reused_symbols,
+            );
+            return;
+        }
+
         let node_content = &content[node.byte_range()];
-        let symbol = get_node_name(&node, content);
+        let local_name = definition_name(&node, content);
+        let symbol = qualify(scope, &local_name);
         let line_count = (end_line - start_line + 1) as usize;
 
         let summary = if is_summary_kind(lang_name, node.kind()) {
@@ -288,30 +1379,79 @@ fn collect_chunks(
         } else {
             None
         };
+        let kind = classify_kind(lang_name, node.kind(), !scope.is_empty());
+        let signature = extract_signature(node, content, lang_name);
+        let visibility = extract_visibility(lang_name, node_content, &local_name);
+        let references = extract_references(node, content, lang_name);
+        let doc_links = summary.as_deref().map(|s| extract_doc_links(s, lang_name)).unwrap_or_default();
+        let is_container = container_kinds_for(lang_name).contains(&node.kind());
 
-        if line_count > max_chunk_lines {
+        if is_container {
+            // A container never gets line-split: its header is already
+            // small, and its members are chunked individually below.
+            let content_str = signature.clone().unwrap_or_else(|| node_content.to_string());
+            let token_count = chunker::estimate_tokens(&content_str);
+            let chunk = Chunk {
+                language: lang_name.to_string(),
+                qualified_symbol: symbol.clone(),
+                parent_symbol: parent_symbol_for(scope),
+                symbol,
+                content: content_str,
+                start_line,
+                end_line,
+                node_kind: node.kind().to_string(),
+                summary,
+                kind,
+                signature,
+                visibility,
+                references,
+                doc_links: doc_links.clone(),
+                token_count,
+            };
+            by_id.insert(node.id(), chunk.clone());
+            chunks.push(chunk);
+        } else if line_count > max_chunk_lines {
             let sub = chunker::split_by_lines(
                 node_content,
                 &symbol,
                 lang_name,
                 start_line,
                 max_chunk_lines,
+                overlap_lines,
+                max_chunk_tokens,
                 node.kind(),
                 summary.as_deref(),
             );
+            // Split into several fallback chunks — no single node id to cache
+            // them under, so they're always recomputed on the next reparse.
             chunks.extend(sub);
         } else {
-            chunks.push(Chunk {
+            let token_count = chunker::estimate_tokens(node_content);
+            let chunk = Chunk {
                 language: lang_name.to_string(),
+                qualified_symbol: symbol.clone(),
+                parent_symbol: parent_symbol_for(scope),
                 symbol,
                 content: node_content.to_string(),
                 start_line,
                 end_line,
                 node_kind: node.kind().to_string(),
                 summary,
-            });
+                kind,
+                signature,
+                visibility,
+                references,
+                doc_links,
+                token_count,
+            };
+            by_id.insert(node.id(), chunk.clone());
+            chunks.push(chunk);
         }
-        // Don't recurse into matched nodes
+
+        recurse_into_container(
+            node, content, lang_name, interesting_kinds, prune_kinds, max_chunk_lines, overlap_lines, max_chunk_tokens,
+            scope, &local_name, incremental, chunks, by_id, reused_symbols,
+        );
         return;
     }
 
@@ -324,7 +1464,71 @@ fn collect_chunks(
 
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        collect_chunks(child, content, lang_name, interesting_kinds, prune_kinds, max_chunk_lines, chunks);
+        collect_chunks(
+            child, content, lang_name, interesting_kinds, prune_kinds, max_chunk_lines, overlap_lines,
+            max_chunk_tokens, scope, incremental, chunks, by_id, reused_symbols,
+        );
+    }
+}
+
+/// If `node`'s byte range doesn't overlap any of the incremental pass's
+/// changed ranges and a chunk was cached for this exact node last time,
+/// return it with its line numbers refreshed to the node's current position
+/// (unaffected nodes can still shift lines when an edit elsewhere in the
+/// file inserts or removes lines above them).
+fn reuse_cached_chunk(
+    node: &tree_sitter::Node,
+    incremental: Option<IncrementalInput>,
+    start_line: u32,
+    end_line: u32,
+) -> Option<Chunk> {
+    let (changed_ranges, cache) = incremental?;
+    let touched = changed_ranges
+        .iter()
+        .any(|r| r.start_byte < node.end_byte() && node.start_byte() < r.end_byte);
+    if touched {
+        return None;
+    }
+    let mut chunk = cache.get(&node.id())?.clone();
+    chunk.start_line = start_line;
+    chunk.end_line = end_line;
+    Some(chunk)
+}
+
+/// Containers (impl/class/object/trait/module bodies) also get their nested
+/// members chunked individually, qualified by this node's name — everything
+/// else (a leaf function, struct, …) has no interesting descendants worth a
+/// separate chunk.
+#[allow(clippy::too_many_arguments)]
+fn recurse_into_container(
+    node: tree_sitter::Node,
+    content: &str,
+    lang_name: &str,
+    interesting_kinds: &[&str],
+    prune_kinds: &[&str],
+    max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+    scope: &[String],
+    local_name: &str,
+    incremental: Option<IncrementalInput>,
+    chunks: &mut Vec<Chunk>,
+    by_id: &mut HashMap<usize, Chunk>,
+    reused_symbols: &mut HashSet<String>,
+) {
+    if !container_kinds_for(lang_name).contains(&node.kind()) {
+        return;
+    }
+    let mut child_scope = scope.to_vec();
+    if !local_name.is_empty() {
+        child_scope.push(local_name.to_string());
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_chunks(
+            child, content, lang_name, interesting_kinds, prune_kinds, max_chunk_lines, overlap_lines,
+            max_chunk_tokens, &child_scope, incremental, chunks, by_id, reused_symbols,
+        );
     }
 }
 
@@ -389,6 +1593,203 @@ fn is_summary_kind(lang: &str, kind: &str) -> bool {
     }
 }
 
+/// Classify a matched node into a coarse `SymbolKind`. `is_nested` is true
+/// when the node was reached by recursing into a container (impl/class/…),
+/// which turns a function-like kind into `Method` rather than `Function`.
+fn classify_kind(lang: &str, node_kind: &str, is_nested: bool) -> SymbolKind {
+    use SymbolKind::*;
+
+    let function_like = matches!(
+        (lang, node_kind),
+        ("rust", "function_item")
+            | ("python", "function_definition")
+            | ("python", "decorated_definition")
+            | ("scala", "function_definition")
+            | ("haskell", "function")
+            | ("ruby", "method")
+            | ("ruby", "singleton_method")
+    );
+    if function_like {
+        return if is_nested { Method } else { Function };
+    }
+
+    match (lang, node_kind) {
+        ("rust", "struct_item") => Struct,
+        ("rust", "union_item") => Struct,
+        ("rust", "enum_item") => Enum,
+        ("rust", "trait_item") => Trait,
+        ("rust", "type_item") => TypeAlias,
+        ("rust", "const_item") | ("rust", "static_item") => Constant,
+        ("rust", "mod_item") => Module,
+
+        ("python", "class_definition") => Class,
+
+        ("java", "method_declaration") | ("java", "constructor_declaration") => Method,
+        ("java", "class_declaration") | ("java", "record_declaration") => Class,
+        ("java", "interface_declaration") | ("java", "annotation_type_declaration") => Interface,
+        ("java", "enum_declaration") => Enum,
+
+        ("csharp", "method_declaration")
+        | ("csharp", "constructor_declaration")
+        | ("csharp", "property_declaration") => Method,
+        ("csharp", "class_declaration")
+        | ("csharp", "struct_declaration")
+        | ("csharp", "record_declaration") => Class,
+        ("csharp", "interface_declaration") => Interface,
+        ("csharp", "enum_declaration") => Enum,
+        ("csharp", "delegate_declaration") => TypeAlias,
+
+        ("scala", "class_definition") => Class,
+        ("scala", "object_definition") => Module,
+        ("scala", "trait_definition") => Trait,
+        ("scala", "enum_definition") => Enum,
+        ("scala", "type_definition") => TypeAlias,
+
+        ("haskell", "data_type") | ("haskell", "newtype") => Struct,
+        ("haskell", "class") => Trait,
+        ("haskell", "type_synomym") | ("haskell", "type_family") => TypeAlias,
+
+        ("javascript", "function_declaration")
+        | ("javascript", "arrow_function")
+        | ("javascript", "generator_function_declaration") => Function,
+        ("javascript", "method_definition") => Method,
+        ("javascript", "class_declaration") => Class,
+
+        ("typescript", "function_declaration") | ("tsx", "function_declaration") => Function,
+        ("typescript", "method_definition") | ("tsx", "method_definition") => Method,
+        ("typescript", "class_declaration") | ("tsx", "class_declaration") => Class,
+        ("typescript", "interface_declaration") | ("tsx", "interface_declaration") => Interface,
+        ("typescript", "type_alias_declaration") | ("tsx", "type_alias_declaration") => TypeAlias,
+        ("typescript", "enum_declaration") | ("tsx", "enum_declaration") => Enum,
+
+        ("go", "function_declaration") => Function,
+        ("go", "method_declaration") => Method,
+        ("go", "type_declaration") => TypeAlias,
+        ("go", "const_declaration") => Constant,
+
+        ("ruby", "class") | ("ruby", "singleton_class") => Class,
+        ("ruby", "module") => Module,
+
+        ("fsharp", "value_declaration") => Function,
+        ("fsharp", "type_defn") => TypeAlias,
+        ("fsharp", "module_defn") | ("fsharp", "namespace") => Module,
+
+        _ => Other,
+    }
+}
+
+/// Extract a one-line declaration head — name, params, return type — with
+/// the body cut off and internal whitespace collapsed. Returns `None` if the
+/// node's text is empty.
+///
+/// Haskell is a special case: a `function` node's own text starts at its
+/// first equation (e.g. `foo x = x + 1`), not its type — the type lives in
+/// a separate `signature` sibling that `prune_kinds_for` keeps
+/// `collect_chunks` from ever visiting directly. When one precedes `node`,
+/// its text (e.g. `foo :: Int -> Int`) is used instead of `node`'s own text.
+fn extract_signature(node: tree_sitter::Node, content: &str, lang: &str) -> Option<String> {
+    if lang == "haskell" {
+        if let Some(sig) = haskell_signature(node, content) {
+            let collapsed = sig.split_whitespace().collect::<Vec<_>>().join(" ");
+            return if collapsed.is_empty() { None } else { Some(collapsed) };
+        }
+    }
+
+    let node_content = &content[node.byte_range()];
+    let head = match lang {
+        "python" => take_until_unbraced_colon(node_content),
+        // Line-oriented: the declaration head is the first physical line
+        // (type signature for Haskell, `let`/`module` binding for F#, `def`
+        // header for Ruby — all single-line by convention).
+        "haskell" | "fsharp" | "ruby" => node_content.lines().next().unwrap_or(node_content).to_string(),
+        // Brace-delimited languages: the head ends where the body begins.
+        _ => node_content.split('{').next().unwrap_or(node_content).to_string(),
+    };
+
+    let collapsed = head.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() { None } else { Some(collapsed) }
+}
+
+/// The `signature` node (Haskell type signature, e.g. `foo :: Int -> Int`)
+/// immediately preceding `node`, if tree-sitter placed one there. Only the
+/// first equation of a multi-clause function has one — later clauses fall
+/// back to `extract_signature`'s normal first-line behavior.
+fn haskell_signature<'a>(node: tree_sitter::Node, content: &'a str) -> Option<&'a str> {
+    let sib = node.prev_named_sibling()?;
+    (sib.kind() == "signature").then(|| content[sib.byte_range()].trim())
+}
+
+/// Python headers end with a `:` that closes the `def`/`class` statement —
+/// skip colons nested inside parameter lists, type hints, or brackets
+/// (e.g. `def f(a: int, b: dict[str, int]) -> int:`).
+fn take_until_unbraced_colon(s: &str) -> String {
+    let mut depth = 0i32;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ':' if depth == 0 => return s[..i].to_string(),
+            _ => {}
+        }
+    }
+    s.to_string()
+}
+
+/// Best-effort declared visibility, from language-specific modifier keywords
+/// or naming conventions. Returns `Unknown` where the language has no
+/// visibility concept at this granularity or none could be detected.
+fn extract_visibility(lang: &str, node_content: &str, symbol_name: &str) -> Visibility {
+    match lang {
+        "rust" => {
+            let trimmed = node_content.trim_start();
+            if trimmed.starts_with("pub(") {
+                Visibility::Internal
+            } else if trimmed.starts_with("pub") {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            }
+        }
+        "go" => {
+            if symbol_name.chars().next().is_some_and(|c| c.is_uppercase()) {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            }
+        }
+        "python" | "ruby" => {
+            if symbol_name.starts_with('_') {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            }
+        }
+        "java" | "csharp" | "scala" | "fsharp" => {
+            let head = node_content.lines().next().unwrap_or(node_content);
+            if head.contains("private") {
+                Visibility::Private
+            } else if head.contains("protected") || head.contains("internal") {
+                Visibility::Internal
+            } else if head.contains("public") {
+                Visibility::Public
+            } else {
+                Visibility::Unknown
+            }
+        }
+        "javascript" | "typescript" | "tsx" => {
+            let head = node_content.lines().next().unwrap_or(node_content);
+            if head.contains("private") {
+                Visibility::Private
+            } else if head.contains("protected") {
+                Visibility::Internal
+            } else {
+                Visibility::Unknown
+            }
+        }
+        _ => Visibility::Unknown,
+    }
+}
+
 /// Returns the comment node kinds for a given language.
 fn comment_kinds_for(lang: &str) -> &'static [&'static str] {
     match lang {
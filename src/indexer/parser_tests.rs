@@ -24,6 +24,30 @@ mod parser_tests {
         chunks.iter().find(|c| c.symbol == symbol).and_then(|c| c.summary.as_deref())
     }
 
+    fn kind_for(
+        chunks: &[crate::indexer::parser::Chunk],
+        symbol: &str,
+    ) -> crate::indexer::parser::SymbolKind {
+        chunks
+            .iter()
+            .find(|c| c.symbol == symbol)
+            .unwrap_or_else(|| panic!("no chunk for symbol '{symbol}'"))
+            .kind
+    }
+
+    fn signature_for<'a>(chunks: &'a [crate::indexer::parser::Chunk], symbol: &str) -> Option<&'a str> {
+        chunks.iter().find(|c| c.symbol == symbol).and_then(|c| c.signature.as_deref())
+    }
+
+    fn doc_links_for<'a>(chunks: &'a [crate::indexer::parser::Chunk], symbol: &str) -> &'a [String] {
+        chunks
+            .iter()
+            .find(|c| c.symbol == symbol)
+            .unwrap_or_else(|| panic!("no chunk for symbol '{symbol}'"))
+            .doc_links
+            .as_slice()
+    }
+
     /// Assert that a summary:
     /// 1. is present (not None),
     /// 2. contains an expected keyword phrase,
@@ -53,13 +77,17 @@ mod parser_tests {
     #[test]
     fn rust_example_chunks() {
         let content = include_str!("../../example/math.rs");
-        let chunks = parse_file(Path::new("math.rs"), content, 40);
+        let chunks = parse_file(Path::new("math.rs"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 5, "expected ≥5 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"add"), "missing symbol 'add'");
         assert!(syms.contains(&"factorial"), "missing symbol 'factorial'");
         assert!(syms.contains(&"max_val"), "missing symbol 'max_val'");
         assert!(syms.contains(&"Stack"), "missing symbol 'Stack'");
+        // `impl Stack { .. }` is a container: its methods are chunked
+        // individually too, qualified by the enclosing type name.
+        assert!(syms.contains(&"Stack::push"), "missing nested symbol 'Stack::push'");
+        assert!(syms.contains(&"Stack::pop"), "missing nested symbol 'Stack::pop'");
         assert!(has_summary(&chunks), "no summaries extracted for rust example");
 
         assert_summary_ok(
@@ -80,6 +108,20 @@ mod parser_tests {
             "stack backed by a Vec",
             &["pub struct", "struct Stack"],
         );
+
+        assert_eq!(kind_for(&chunks, "Stack"), crate::indexer::parser::SymbolKind::Struct);
+        assert_eq!(kind_for(&chunks, "add"), crate::indexer::parser::SymbolKind::Function);
+        assert_eq!(kind_for(&chunks, "Stack::push"), crate::indexer::parser::SymbolKind::Method);
+
+        let add_sig = signature_for(&chunks, "add").expect("missing signature for 'add'");
+        assert!(
+            add_sig.contains("(a: i32, b: i32) -> i32"),
+            "signature for 'add' should contain the params and return type, got: {add_sig:?}"
+        );
+        assert!(
+            !add_sig.contains('{'),
+            "signature for 'add' must not include the function body, got: {add_sig:?}"
+        );
     }
 
     // ── Python ────────────────────────────────────────────────────────────────
@@ -87,12 +129,19 @@ mod parser_tests {
     #[test]
     fn python_example_chunks() {
         let content = include_str!("../../example/utils.py");
-        let chunks = parse_file(Path::new("utils.py"), content, 40);
+        let chunks = parse_file(Path::new("utils.py"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 3, "expected ≥3 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"parse_args"), "missing symbol 'parse_args'");
         assert!(syms.contains(&"chunk_list"), "missing symbol 'chunk_list'");
         assert!(syms.contains(&"RingBuffer"), "missing symbol 'RingBuffer'");
+        // `class RingBuffer` is a container: its methods are also chunked
+        // individually, qualified as `RingBuffer::<method>`.
+        assert!(
+            syms.iter().any(|s| s.starts_with("RingBuffer::")),
+            "expected nested members under 'RingBuffer::', got {:?}",
+            syms
+        );
         assert!(has_summary(&chunks), "no summaries extracted for python example");
 
         assert_summary_ok(
@@ -124,7 +173,7 @@ mod parser_tests {
     #[test]
     fn go_example_chunks() {
         let content = include_str!("../../example/greet.go");
-        let chunks = parse_file(Path::new("greet.go"), content, 40);
+        let chunks = parse_file(Path::new("greet.go"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 3, "expected ≥3 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"Greet"), "missing symbol 'Greet'");
@@ -151,10 +200,17 @@ mod parser_tests {
     #[test]
     fn java_example_chunks() {
         let content = include_str!("../../example/shapes.java");
-        let chunks = parse_file(Path::new("shapes.java"), content, 80);
+        let chunks = parse_file(Path::new("shapes.java"), content, 80, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 2, "expected ≥2 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"Point"), "missing symbol 'Point'");
+        // `class Point` is a container: its members are also chunked
+        // individually, qualified as `Point::<member>`.
+        assert!(
+            syms.iter().any(|s| s.starts_with("Point::")),
+            "expected nested members under 'Point::', got {:?}",
+            syms
+        );
         assert!(has_summary(&chunks), "no summaries extracted for java example");
 
         assert_summary_ok(
@@ -176,10 +232,17 @@ mod parser_tests {
     #[test]
     fn csharp_example_chunks() {
         let content = include_str!("../../example/collections.cs");
-        let chunks = parse_file(Path::new("collections.cs"), content, 80);
+        let chunks = parse_file(Path::new("collections.cs"), content, 80, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 2, "expected ≥2 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"MinHeap"), "missing symbol 'MinHeap'");
+        // `class MinHeap` is a container: its methods are also chunked
+        // individually, qualified as `MinHeap::<method>`.
+        assert!(
+            syms.iter().any(|s| s.starts_with("MinHeap::")),
+            "expected nested members under 'MinHeap::', got {:?}",
+            syms
+        );
         assert!(has_summary(&chunks), "no summaries extracted for csharp example");
 
         // XML doc tags must be stripped — summary must not contain <summary> etc.
@@ -202,10 +265,17 @@ mod parser_tests {
     #[test]
     fn scala_example_chunks() {
         let content = include_str!("../../example/algebra.scala");
-        let chunks = parse_file(Path::new("algebra.scala"), content, 40);
+        let chunks = parse_file(Path::new("algebra.scala"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 2, "expected ≥2 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"Rational"), "missing symbol 'Rational'");
+        // `case class Rational` is a container: its members are also chunked
+        // individually, qualified as `Rational::<member>`.
+        assert!(
+            syms.iter().any(|s| s.starts_with("Rational::")),
+            "expected nested members under 'Rational::', got {:?}",
+            syms
+        );
         assert!(has_summary(&chunks), "no summaries extracted for scala example");
 
         assert_summary_ok(
@@ -233,7 +303,7 @@ mod parser_tests {
     #[test]
     fn haskell_example_chunks() {
         let content = include_str!("../../example/Combinatorics.hs");
-        let chunks = parse_file(Path::new("Combinatorics.hs"), content, 40);
+        let chunks = parse_file(Path::new("Combinatorics.hs"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 3, "expected ≥3 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"choose"), "missing symbol 'choose'");
@@ -271,7 +341,7 @@ mod parser_tests {
     #[test]
     fn javascript_example_chunks() {
         let content = include_str!("../../example/dom.js");
-        let chunks = parse_file(Path::new("dom.js"), content, 40);
+        let chunks = parse_file(Path::new("dom.js"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 3, "expected ≥3 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"debounce"), "missing symbol 'debounce'");
@@ -298,7 +368,7 @@ mod parser_tests {
     #[test]
     fn typescript_example_chunks() {
         let content = include_str!("../../example/validation.ts");
-        let chunks = parse_file(Path::new("validation.ts"), content, 40);
+        let chunks = parse_file(Path::new("validation.ts"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 3, "expected ≥3 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"validateEmail"), "missing symbol 'validateEmail'");
@@ -325,7 +395,7 @@ mod parser_tests {
     #[test]
     fn tsx_example_chunks() {
         let content = include_str!("../../example/components.tsx");
-        let chunks = parse_file(Path::new("components.tsx"), content, 40);
+        let chunks = parse_file(Path::new("components.tsx"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 3, "expected ≥3 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"Counter"), "missing symbol 'Counter'");
@@ -358,12 +428,19 @@ mod parser_tests {
     #[test]
     fn ruby_example_chunks() {
         let content = include_str!("../../example/text.rb");
-        let chunks = parse_file(Path::new("text.rb"), content, 40);
+        let chunks = parse_file(Path::new("text.rb"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 4, "expected ≥4 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"camelize"), "missing symbol 'camelize'");
         assert!(syms.contains(&"word_frequency"), "missing symbol 'word_frequency'");
         assert!(syms.contains(&"LruCache"), "missing symbol 'LruCache'");
+        // `class LruCache` is a container: its methods are also chunked
+        // individually, qualified as `LruCache::<method>`.
+        assert!(
+            syms.iter().any(|s| s.starts_with("LruCache::")),
+            "expected nested members under 'LruCache::', got {:?}",
+            syms
+        );
         assert!(has_summary(&chunks), "no summaries extracted for ruby example");
 
         assert_summary_ok(
@@ -385,7 +462,7 @@ mod parser_tests {
     #[test]
     fn fsharp_example_chunks() {
         let content = include_str!("../../example/Numerics.fs");
-        let chunks = parse_file(Path::new("Numerics.fs"), content, 40);
+        let chunks = parse_file(Path::new("Numerics.fs"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 3, "expected ≥3 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"pow"), "missing symbol 'pow'");
@@ -418,7 +495,7 @@ mod parser_tests {
     #[test]
     fn kotlin_example_chunks() {
         let content = include_str!("../../example/geometry.kt");
-        let chunks = parse_file(Path::new("geometry.kt"), content, 40);
+        let chunks = parse_file(Path::new("geometry.kt"), content, 40, 0, 100000);
         let syms = symbols(&chunks);
         assert!(chunks.len() >= 4, "expected ≥4 chunks, got {}: {:?}", chunks.len(), syms);
         assert!(syms.contains(&"Vector2"), "missing symbol 'Vector2'");
@@ -498,7 +575,7 @@ mod parser_tests {
                 "foo :: Int -> Int\n",
                 "foo x = x + 1\n",
             );
-            let chunks = parse_file(Path::new("t.hs"), src, 80);
+            let chunks = parse_file(Path::new("t.hs"), src, 80, 0, 100000);
             assert_no_summary_for(&chunks, "foo", "haskell plain -- comment");
         }
 
@@ -508,7 +585,7 @@ mod parser_tests {
                 "// plain implementation note, not a doc comment\n",
                 "fn add(a: i32, b: i32) -> i32 { a + b }\n",
             );
-            let chunks = parse_file(Path::new("t.rs"), src, 80);
+            let chunks = parse_file(Path::new("t.rs"), src, 80, 0, 100000);
             assert_no_summary_for(&chunks, "add", "rust plain // comment");
         }
 
@@ -518,7 +595,7 @@ mod parser_tests {
                 "// plain implementation note, not javadoc\n",
                 "public static int add(int a, int b) { return a + b; }\n",
             );
-            let chunks = parse_file(Path::new("t.java"), src, 80);
+            let chunks = parse_file(Path::new("t.java"), src, 80, 0, 100000);
             assert_no_summary_for(&chunks, "add", "java plain // comment");
         }
 
@@ -528,7 +605,7 @@ mod parser_tests {
                 "// plain implementation note, not jsdoc\n",
                 "function add(a, b) { return a + b; }\n",
             );
-            let chunks = parse_file(Path::new("t.js"), src, 80);
+            let chunks = parse_file(Path::new("t.js"), src, 80, 0, 100000);
             assert_no_summary_for(&chunks, "add", "javascript plain // comment");
         }
 
@@ -538,7 +615,7 @@ mod parser_tests {
                 "// plain implementation note, not jsdoc\n",
                 "function add(a: number, b: number): number { return a + b; }\n",
             );
-            let chunks = parse_file(Path::new("t.ts"), src, 80);
+            let chunks = parse_file(Path::new("t.ts"), src, 80, 0, 100000);
             assert_no_summary_for(&chunks, "add", "typescript plain // comment");
         }
 
@@ -548,20 +625,17 @@ mod parser_tests {
                 "// plain implementation note, not scaladoc\n",
                 "def add(a: Int, b: Int): Int = a + b\n",
             );
-            let chunks = parse_file(Path::new("t.scala"), src, 80);
+            let chunks = parse_file(Path::new("t.scala"), src, 80, 0, 100000);
             assert_no_summary_for(&chunks, "add", "scala plain // comment");
         }
 
         // ── C#: `//` (not `///` XML doc) ─────────────────────────────────────
-        // Note: collect_chunks doesn't recurse into class_declaration, so we
-        // test a top-level class (the outermost interesting node) rather than
-        // a method inside one.
         {
             let src = concat!(
                 "// plain implementation note, not xml doc\n",
                 "public class Widget { }\n",
             );
-            let chunks = parse_file(Path::new("t.cs"), src, 80);
+            let chunks = parse_file(Path::new("t.cs"), src, 80, 0, 100000);
             assert_no_summary_for(&chunks, "Widget", "csharp plain // comment");
         }
 
@@ -572,7 +646,7 @@ mod parser_tests {
                 "// plain implementation note, not xml doc\n",
                 "let add (a: int) (b: int) : int = a + b\n",
             );
-            let chunks = parse_file(Path::new("t.fs"), src, 80);
+            let chunks = parse_file(Path::new("t.fs"), src, 80, 0, 100000);
             assert_no_summary_for(&chunks, "add", "fsharp plain // comment");
         }
 
@@ -582,8 +656,334 @@ mod parser_tests {
                 "// plain implementation note, not kdoc\n",
                 "fun add(a: Int, b: Int): Int = a + b\n",
             );
-            let chunks = parse_file(Path::new("t.kt"), src, 80);
+            let chunks = parse_file(Path::new("t.kt"), src, 80, 0, 100000);
             assert_no_summary_for(&chunks, "add", "kotlin plain // comment");
         }
     }
+
+    // ── doc links ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn rust_doc_links_extracted_from_intra_doc_references() {
+        let src = "/// See [Stack] and [`Stack::push`] for details.\nfn helper() {}\n";
+        let chunks = parse_file(Path::new("t.rs"), src, 80, 0, 100000);
+        assert_eq!(doc_links_for(&chunks, "helper"), &["Stack".to_string(), "Stack::push".to_string()]);
+    }
+
+    #[test]
+    fn rust_doc_links_skip_markdown_links_with_a_url() {
+        let src = "/// See [the spec](https://example.com) for details.\nfn helper() {}\n";
+        let chunks = parse_file(Path::new("t.rs"), src, 80, 0, 100000);
+        assert!(doc_links_for(&chunks, "helper").is_empty());
+    }
+
+    #[test]
+    fn java_doc_links_extracted_from_link_and_see_tags() {
+        let src = "/**\n * Uses {@link Helper#run} under the hood.\n * @see Other\n */\nvoid task() {}\n";
+        let chunks = parse_file(Path::new("t.java"), src, 80, 0, 100000);
+        assert_eq!(doc_links_for(&chunks, "task"), &["Helper".to_string(), "Other".to_string()]);
+    }
+
+    #[test]
+    fn python_doc_links_extracted_from_sphinx_roles() {
+        let src = "def run():\n    \"\"\"Delegates to :func:`helper` and :class:`Stack`.\"\"\"\n    pass\n";
+        let chunks = parse_file(Path::new("t.py"), src, 80, 0, 100000);
+        assert_eq!(doc_links_for(&chunks, "run"), &["helper".to_string(), "Stack".to_string()]);
+    }
+
+    #[test]
+    fn haskell_doc_links_extracted_from_haddock_quoting() {
+        let src = "-- | Calls 'helper' from \"Data.Stack\".\nrun :: Int -> Int\nrun x = x\n";
+        let chunks = parse_file(Path::new("t.hs"), src, 80, 0, 100000);
+        assert_eq!(doc_links_for(&chunks, "run"), &["helper".to_string(), "Data.Stack".to_string()]);
+    }
+
+    // ── IncrementalParser ─────────────────────────────────────────────────────
+
+    #[test]
+    fn incremental_parser_returns_none_for_unsupported_extension() {
+        assert!(crate::indexer::parser::IncrementalParser::new(Path::new("t.kt"), 80, 0, 100000).is_none());
+    }
+
+    #[test]
+    fn incremental_parser_reuses_untouched_chunks_and_diffs_only_the_edit() {
+        use crate::indexer::parser::IncrementalParser;
+        use tree_sitter::{InputEdit, Point};
+
+        fn point_at(s: &str, byte: usize) -> Point {
+            let row = s[..byte].matches('\n').count();
+            let col = byte - s[..byte].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            Point { row, column: col }
+        }
+
+        let old_src = "fn add(a: i32, b: i32) -> i32 { a + b }\n\nfn square(x: i32) -> i32 { x * x }\n";
+        let mut incremental = IncrementalParser::new(Path::new("t.rs"), 80, 0, 100000).expect("rust grammar available");
+
+        let first = incremental.parse(old_src);
+        assert!(symbols(&first).contains(&"add"));
+        assert!(symbols(&first).contains(&"square"));
+
+        // Edit only `square`'s body: `x * x` -> `x * x * 2`. `add` is
+        // untouched and should come back out of the cache as-is.
+        let start_byte = old_src.find("x * x").unwrap() + "x * x".len();
+        let old_end_byte = start_byte;
+        let inserted = " * 2";
+        let new_src = format!("{}{}{}", &old_src[..start_byte], inserted, &old_src[old_end_byte..]);
+        let new_end_byte = start_byte + inserted.len();
+
+        let edit = InputEdit {
+            start_byte,
+            old_end_byte,
+            new_end_byte,
+            start_position: point_at(old_src, start_byte),
+            old_end_position: point_at(old_src, old_end_byte),
+            new_end_position: point_at(&new_src, new_end_byte),
+        };
+
+        let (chunks, diff) = incremental.reparse(old_src, &new_src, &[edit]);
+        assert!(symbols(&chunks).contains(&"add"));
+        assert!(symbols(&chunks).contains(&"square"));
+        assert!(diff.added.is_empty(), "nothing was added: {:?}", diff.added);
+        assert!(diff.removed.is_empty(), "nothing was removed: {:?}", diff.removed);
+        assert_eq!(diff.changed, vec!["square".to_string()]);
+
+        // `add` was reused wholesale — its signature should still be intact.
+        let add_sig = signature_for(&chunks, "add").expect("add should still have a signature");
+        assert!(add_sig.contains("(a: i32, b: i32) -> i32"));
+    }
+
+    #[test]
+    fn incremental_parser_reparse_without_prior_tree_is_a_full_parse() {
+        use crate::indexer::parser::IncrementalParser;
+
+        let src = "fn add(a: i32, b: i32) -> i32 { a + b }\n";
+        let mut incremental = IncrementalParser::new(Path::new("t.rs"), 80, 0, 100000).expect("rust grammar available");
+        let (chunks, diff) = incremental.reparse("", src, &[]);
+        assert!(symbols(&chunks).contains(&"add"));
+        assert_eq!(diff.added, vec!["add".to_string()]);
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn incremental_parser_reparse_diff_computes_edits_from_old_and_new_content() {
+        use crate::indexer::parser::IncrementalParser;
+
+        let old_src = "fn add(a: i32, b: i32) -> i32 { a + b }\n\nfn square(x: i32) -> i32 { x * x }\n";
+        let new_src = old_src.replace("x * x }", "x * x * 2 }");
+
+        let mut incremental = IncrementalParser::new(Path::new("t.rs"), 80, 0, 100000).expect("rust grammar available");
+        incremental.parse(old_src);
+
+        let (chunks, diff) = incremental.reparse_diff(old_src, &new_src);
+        assert!(symbols(&chunks).contains(&"add"));
+        assert!(symbols(&chunks).contains(&"square"));
+        assert_eq!(diff.changed, vec!["square".to_string()]);
+
+        let add_sig = signature_for(&chunks, "add").expect("add should still have a signature");
+        assert!(add_sig.contains("(a: i32, b: i32) -> i32"));
+    }
+
+    // ── user-supplied query files ───────────────────────────────────────────
+
+    #[test]
+    fn parse_file_with_queries_falls_back_to_builtin_without_a_query_dir() {
+        use crate::indexer::parser::parse_file_with_queries;
+
+        let src = "fn add(a: i32, b: i32) -> i32 { a + b }\n";
+        let chunks = parse_file_with_queries(Path::new("t.rs"), src, 80, 0, 100000, None);
+        assert!(symbols(&chunks).contains(&"add"));
+    }
+
+    #[test]
+    fn parse_file_with_queries_uses_a_custom_query_to_capture_an_otherwise_skipped_kind() {
+        use crate::indexer::parser::parse_file_with_queries;
+
+        // `enum_variant` isn't in `RUST_KINDS` — only the enclosing `enum_item`
+        // is chunked by the built-in extraction, so individual variants never
+        // appear as their own symbol. A custom query can still surface them.
+        let query_dir = std::env::temp_dir().join(format!(
+            "maharajah-query-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&query_dir).expect("create temp query dir");
+        std::fs::write(
+            query_dir.join("rust.scm"),
+            "(enum_variant name: (identifier) @symbol.name) @symbol.def\n",
+        )
+        .expect("write rust.scm");
+
+        let src = "enum Direction {\n    North,\n    South,\n}\n";
+        let chunks = parse_file_with_queries(Path::new("t.rs"), src, 80, 0, 100000, Some(&query_dir));
+
+        std::fs::remove_dir_all(&query_dir).ok();
+
+        assert!(
+            symbols(&chunks).contains(&"North") && symbols(&chunks).contains(&"South"),
+            "expected variant-level symbols from the custom query, got {:?}",
+            symbols(&chunks)
+        );
+    }
+
+    // ── ChunkIterator ───────────────────────────────────────────────────────
+
+    #[test]
+    fn chunk_iterator_yields_the_same_symbols_as_parse_file() {
+        use crate::indexer::parser::ChunkIterator;
+
+        let src = "fn add(a: i32, b: i32) -> i32 { a + b }\n\nstruct Stack<T> { data: Vec<T> }\n";
+        let eager = parse_file(Path::new("t.rs"), src, 80, 0, 100000);
+
+        let streamed: Vec<crate::indexer::parser::Chunk> = ChunkIterator::new(Path::new("t.rs"), src, 80, 0, 100000)
+            .expect("rust grammar available")
+            .filter_map(Result::ok)
+            .collect();
+
+        assert_eq!(symbols(&eager), symbols(&streamed));
+    }
+
+    #[test]
+    fn chunk_iterator_supports_early_termination_without_materializing_the_rest() {
+        use crate::indexer::parser::ChunkIterator;
+
+        let src = "fn add(a: i32, b: i32) -> i32 { a + b }\n\nfn square(x: i32) -> i32 { x * x }\n";
+        let first: Vec<_> = ChunkIterator::new(Path::new("t.rs"), src, 80, 0, 100000)
+            .expect("rust grammar available")
+            .take(1)
+            .filter_map(Result::ok)
+            .collect();
+
+        assert_eq!(symbols(&first), vec!["add"]);
+    }
+
+    // ── token-budgeted line splitting ───────────────────────────────────────
+
+    #[test]
+    fn split_by_lines_stops_a_window_early_when_the_token_budget_is_hit() {
+        use crate::indexer::chunker::split_by_lines;
+
+        // Each line is ~5 tokens; a budget of 12 tokens should only fit two
+        // lines per window even though `max_lines` alone would allow five.
+        let content = "aa bb cc dd ee\nff gg hh ii jj\nkk ll mm nn oo\n";
+        let chunks = split_by_lines(content, "big_fn", "rust", 0, 5, 0, 12, "function_item", None);
+
+        assert!(chunks.len() > 1, "token budget should have forced more than one window");
+        for c in &chunks {
+            assert!(
+                c.token_count <= 12,
+                "chunk exceeded the token budget: {} tokens in {:?}",
+                c.token_count,
+                c.content
+            );
+        }
+    }
+
+    #[test]
+    fn split_by_lines_hard_splits_a_single_line_that_alone_exceeds_the_budget() {
+        use crate::indexer::chunker::split_by_lines;
+
+        let content = "aa bb cc dd ee ff gg hh ii jj\n";
+        let chunks = split_by_lines(content, "minified", "rust", 0, 10, 0, 3, "function_item", None);
+
+        assert!(chunks.len() > 1, "the one over-budget line should have been hard-split");
+        for c in &chunks {
+            assert!(c.token_count <= 3, "hard-split piece exceeded the token budget: {:?}", c.content);
+        }
+    }
+
+    #[test]
+    fn estimate_tokens_counts_words_and_punctuation() {
+        use crate::indexer::chunker::estimate_tokens;
+
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("foo"), 1);
+        assert_eq!(estimate_tokens("foo.bar"), 3);
+    }
+
+    // ── overlapping windows ─────────────────────────────────────────────────
+
+    #[test]
+    fn split_by_lines_overlaps_consecutive_windows_by_the_requested_line_count() {
+        use crate::indexer::chunker::split_by_lines;
+
+        let lines: Vec<String> = (0..10).map(|i| format!("line{i}")).collect();
+        let content = lines.join("\n");
+        let chunks = split_by_lines(&content, "f", "rust", 0, 4, 2, 100000, "function_item", None);
+
+        assert!(chunks.len() >= 2, "expected at least two overlapping windows");
+        for pair in chunks.windows(2) {
+            let (prev, next) = (&pair[0], &pair[1]);
+            // The stride is max_lines - overlap_lines == 2, so the next window
+            // should start 2 lines after the previous one started, and the two
+            // should share lines in between (next's start falls strictly
+            // before prev's end).
+            assert_eq!(next.start_line, prev.start_line + 2);
+            assert!(
+                next.start_line <= prev.end_line,
+                "windows should overlap: prev ends at {}, next starts at {}",
+                prev.end_line,
+                next.start_line
+            );
+        }
+    }
+
+    #[test]
+    fn split_by_lines_start_and_end_line_reflect_the_true_window_span() {
+        use crate::indexer::chunker::split_by_lines;
+
+        let lines: Vec<String> = (0..10).map(|i| format!("line{i}")).collect();
+        let content = lines.join("\n");
+        let start_offset = 7;
+        let chunks = split_by_lines(&content, "f", "rust", start_offset, 4, 2, 100000, "function_item", None);
+
+        for c in &chunks {
+            let span = (c.end_line - c.start_line + 1) as usize;
+            assert_eq!(
+                c.content.lines().count(),
+                span,
+                "content line count should match start_line/end_line span"
+            );
+            assert!(c.start_line >= start_offset);
+        }
+    }
+
+    #[test]
+    fn split_by_lines_does_not_duplicate_a_final_window_fully_covered_by_its_predecessor() {
+        use crate::indexer::chunker::split_by_lines;
+
+        // 8 lines, max_lines=4, overlap=2 => stride=2: windows would start at
+        // 0, 2, 4, 6 — the window at offset 6 (lines 6..8, since only two
+        // lines remain) is entirely contained in the window at offset 4
+        // (lines 4..8), so it must be suppressed rather than re-emitted.
+        let lines: Vec<String> = (0..8).map(|i| format!("line{i}")).collect();
+        let content = lines.join("\n");
+        let chunks = split_by_lines(&content, "f", "rust", 0, 4, 2, 100000, "function_item", None);
+
+        let last = chunks.last().expect("at least one window");
+        assert_eq!(
+            last.end_line,
+            7,
+            "the last window should reach the final line of content"
+        );
+        for pair in chunks.windows(2) {
+            assert!(
+                pair[1].end_line > pair[0].end_line,
+                "no window should be a subset of the one before it"
+            );
+        }
+    }
+
+    #[test]
+    fn split_by_lines_clamps_stride_to_at_least_one_when_overlap_meets_or_exceeds_max_lines() {
+        use crate::indexer::chunker::split_by_lines;
+
+        let lines: Vec<String> = (0..5).map(|i| format!("line{i}")).collect();
+        let content = lines.join("\n");
+        // overlap_lines >= max_lines would make a naive stride zero (or
+        // negative) and loop forever; it must clamp to 1 and still terminate.
+        let chunks = split_by_lines(&content, "f", "rust", 0, 2, 5, 100000, "function_item", None);
+
+        assert!(!chunks.is_empty());
+        assert_eq!(chunks.last().unwrap().end_line, 4);
+    }
 }
@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use crate::db::cache::{hash_content, EmbeddingCache};
+use crate::embed::Embedder;
+
+/// Maximum number of retries for a batch embed call that fails transiently
+/// (network hiccup, backend overload) before the batch is dropped.
+const MAX_RETRIES: u32 = 3;
+/// Initial backoff delay; doubled after each retry.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Truncate `text` to the longest char-prefix whose `estimate_tokens` fits
+/// within `max_tokens`, so a single outsized chunk can never blow the budget
+/// of a batch all by itself. A no-op when `text` already fits.
+fn truncate_to_budget(embedder: &dyn Embedder, text: &str, max_tokens: usize) -> String {
+    if embedder.estimate_tokens(text) <= max_tokens {
+        return text.to_string();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut lo = 0usize;
+    let mut hi = chars.len();
+    while lo < hi {
+        let mid = lo + (hi - lo + 1) / 2;
+        let candidate: String = chars[..mid].iter().collect();
+        if embedder.estimate_tokens(&candidate) <= max_tokens {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    chars[..lo].iter().collect()
+}
+
+/// Greedily embed `texts` in batches that stay under `max_tokens`, using the
+/// embedding cache to skip anything already embedded and the embedder's
+/// native batch call (with retry-with-backoff on transient failures) for
+/// everything else.
+///
+/// Batches never span files: each call embeds one file's chunk/summary
+/// texts, and the caller writes that file's records to the store only once
+/// every text in `texts` has resolved to a vector (or failed), so a crash
+/// mid-flush never leaves a file half-indexed.
+///
+/// Returns one `Option<Vec<f32>>` per input text, in the same order, `None`
+/// where embedding failed.
+pub async fn embed_batch_with_budget(
+    embedder: &dyn Embedder,
+    cache: &EmbeddingCache,
+    cache_enabled: bool,
+    model_id: &str,
+    texts: &[String],
+    max_tokens: usize,
+) -> Vec<Option<Vec<f32>>> {
+    let mut results: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+
+    // Truncate any chunk that alone would bust the budget, so a single
+    // outsized batch-of-one can never choke the embedder.
+    let prepared: Vec<String> = texts
+        .iter()
+        .map(|t| truncate_to_budget(embedder, t, max_tokens))
+        .collect();
+
+    // Resolve cache hits up front; only misses occupy the token budget.
+    // With the cache disabled (`config.cache.enabled = false`), every text
+    // is treated as a miss so nothing is looked up or written back.
+    let mut misses: Vec<usize> = Vec::new();
+    for (i, text) in prepared.iter().enumerate() {
+        if !cache_enabled {
+            misses.push(i);
+            continue;
+        }
+        let hash = hash_content(text);
+        match cache.get(&hash, model_id).await {
+            Ok(Some(vector)) => results[i] = Some(vector),
+            _ => misses.push(i),
+        }
+    }
+
+    // Pack misses into token-budgeted batches.
+    let mut batch: Vec<usize> = Vec::new();
+    let mut batch_tokens = 0usize;
+
+    async fn flush(
+        embedder: &dyn Embedder,
+        cache: &EmbeddingCache,
+        cache_enabled: bool,
+        model_id: &str,
+        texts: &[String],
+        indices: &[usize],
+        results: &mut [Option<Vec<f32>>],
+    ) {
+        if indices.is_empty() {
+            return;
+        }
+        let batch_texts: Vec<String> = indices.iter().map(|&i| texts[i].clone()).collect();
+
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 0;
+        loop {
+            match embedder.embed_code_batch(&batch_texts).await {
+                Ok(vectors) => {
+                    for (&idx, vector) in indices.iter().zip(vectors) {
+                        if cache_enabled {
+                            let hash = hash_content(&texts[idx]);
+                            if let Err(e) = cache.put(&hash, model_id, &vector).await {
+                                tracing::warn!("failed to populate embedding cache: {e}");
+                            }
+                        }
+                        results[idx] = Some(vector);
+                    }
+                    return;
+                }
+                Err(e) if attempt < MAX_RETRIES => {
+                    attempt += 1;
+                    tracing::warn!(
+                        "embed batch failed ({} chunk(s), attempt {attempt}/{MAX_RETRIES}): {e}, \
+                         retrying in {backoff:?}"
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "batch embed failed for {} chunk(s) after {MAX_RETRIES} retries: {e}",
+                        indices.len()
+                    );
+                    return;
+                }
+            }
+        }
+    }
+
+    for idx in misses {
+        let tokens = embedder.estimate_tokens(&prepared[idx]);
+        if !batch.is_empty() && batch_tokens + tokens > max_tokens {
+            flush(embedder, cache, cache_enabled, model_id, &prepared, &batch, &mut results).await;
+            batch.clear();
+            batch_tokens = 0;
+        }
+        batch.push(idx);
+        batch_tokens += tokens;
+    }
+    flush(embedder, cache, cache_enabled, model_id, &prepared, &batch, &mut results).await;
+
+    results
+}
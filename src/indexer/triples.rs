@@ -0,0 +1,139 @@
+//! Dictionary-encoded RDF-style triple index over a file's parsed `Chunk`s,
+//! so downstream tools can query extracted code structure ("all summaries
+//! for symbol `add`", "all symbols in `t.kt`") without re-parsing or
+//! re-walking the chunk list.
+//!
+//! Every subject/predicate/object term is interned into a flat string table
+//! and triples are stored as `(u32, u32, u32)` id-triples, sorted by
+//! subject, so a subject-bound lookup is a binary search into the table
+//! rather than a full scan.
+
+#[path = "triples_tests.rs"]
+mod triples_tests;
+
+use std::collections::HashMap;
+
+use crate::indexer::parser::Chunk;
+
+/// `file --contains--> symbol`
+pub const PRED_CONTAINS: &str = "contains";
+/// `symbol --language--> language`
+pub const PRED_LANGUAGE: &str = "language";
+/// `symbol --summary--> summary text` (only emitted when the chunk has one)
+pub const PRED_SUMMARY: &str = "summary";
+/// `symbol --byteRange--> "<start_line>-<end_line>"`
+pub const PRED_BYTE_RANGE: &str = "byteRange";
+
+pub struct TripleStore {
+    dictionary: Vec<String>,
+    term_ids: HashMap<String, u32>,
+    /// Sorted by subject id, then predicate id, then object id.
+    triples: Vec<(u32, u32, u32)>,
+}
+
+impl TripleStore {
+    /// Emit triples for every chunk in `chunks`, parsed from `file_path`:
+    /// a `contains` edge from the file to each symbol, and `language`,
+    /// `byteRange`, and (when present) `summary` edges from the symbol.
+    pub fn from_chunks(file_path: &str, chunks: &[Chunk]) -> TripleStore {
+        let mut store = TripleStore {
+            dictionary: Vec::new(),
+            term_ids: HashMap::new(),
+            triples: Vec::new(),
+        };
+
+        for chunk in chunks {
+            store.add(file_path, PRED_CONTAINS, &chunk.symbol);
+            store.add(&chunk.symbol, PRED_LANGUAGE, &chunk.language);
+            store.add(
+                &chunk.symbol,
+                PRED_BYTE_RANGE,
+                &format!("{}-{}", chunk.start_line, chunk.end_line),
+            );
+            if let Some(summary) = &chunk.summary {
+                store.add(&chunk.symbol, PRED_SUMMARY, summary);
+            }
+        }
+
+        store.triples.sort_unstable();
+        store.triples.dedup();
+        store
+    }
+
+    fn intern(&mut self, term: &str) -> u32 {
+        if let Some(&id) = self.term_ids.get(term) {
+            return id;
+        }
+        let id = self.dictionary.len() as u32;
+        self.dictionary.push(term.to_string());
+        self.term_ids.insert(term.to_string(), id);
+        id
+    }
+
+    fn add(&mut self, subject: &str, predicate: &str, object: &str) {
+        let s = self.intern(subject);
+        let p = self.intern(predicate);
+        let o = self.intern(object);
+        self.triples.push((s, p, o));
+    }
+
+    fn term(&self, id: u32) -> &str {
+        &self.dictionary[id as usize]
+    }
+
+    /// The id for `term`, if it was ever interned. A pattern position bound
+    /// to a term that was never seen can never match, so callers short-
+    /// circuit to an empty result rather than scanning.
+    fn id_of(&self, term: &str) -> Option<u32> {
+        self.term_ids.get(term).copied()
+    }
+
+    /// All triples matching a pattern; `None` in any position is a
+    /// wildcard. A bound subject narrows the scan to its contiguous run in
+    /// the subject-sorted table via binary search instead of a full scan.
+    pub fn triples_matching(
+        &self,
+        subject: Option<&str>,
+        predicate: Option<&str>,
+        object: Option<&str>,
+    ) -> Vec<(&str, &str, &str)> {
+        let subject_id = match subject {
+            Some(s) => match self.id_of(s) {
+                Some(id) => Some(id),
+                None => return Vec::new(),
+            },
+            None => None,
+        };
+        let predicate_id = match predicate {
+            Some(p) => match self.id_of(p) {
+                Some(id) => Some(id),
+                None => return Vec::new(),
+            },
+            None => None,
+        };
+        let object_id = match object {
+            Some(o) => match self.id_of(o) {
+                Some(id) => Some(id),
+                None => return Vec::new(),
+            },
+            None => None,
+        };
+
+        let candidates: &[(u32, u32, u32)] = match subject_id {
+            Some(s_id) => {
+                let start = self.triples.partition_point(|&(s, _, _)| s < s_id);
+                let end = self.triples.partition_point(|&(s, _, _)| s <= s_id);
+                &self.triples[start..end]
+            }
+            None => &self.triples,
+        };
+
+        candidates
+            .iter()
+            .filter(|&&(_, p, o)| {
+                predicate_id.map_or(true, |id| p == id) && object_id.map_or(true, |id| o == id)
+            })
+            .map(|&(s, p, o)| (self.term(s), self.term(p), self.term(o)))
+            .collect()
+    }
+}
@@ -0,0 +1,62 @@
+/// Triple-store tests, built on top of `parser::parse_file` so the emitted
+/// triples are exactly what the indexing pipeline would produce.
+
+#[cfg(test)]
+mod triples_tests {
+    use crate::indexer::parser::parse_file;
+    use crate::indexer::triples::{TripleStore, PRED_CONTAINS, PRED_LANGUAGE, PRED_SUMMARY};
+    use std::path::Path;
+
+    fn store_for(path: &str, content: &str) -> TripleStore {
+        let chunks = parse_file(Path::new(path), content, 40, 0, 100000);
+        TripleStore::from_chunks(path, &chunks)
+    }
+
+    #[test]
+    fn lists_all_symbols_contained_in_a_file() {
+        let content = include_str!("../../example/math.rs");
+        let store = store_for("math.rs", content);
+
+        let contained = store.triples_matching(Some("math.rs"), Some(PRED_CONTAINS), None);
+        assert!(!contained.is_empty());
+        assert!(contained.iter().any(|&(_, _, o)| o == "factorial"));
+        assert!(contained
+            .iter()
+            .all(|&(s, p, _)| s == "math.rs" && p == PRED_CONTAINS));
+    }
+
+    #[test]
+    fn looks_up_language_for_a_symbol() {
+        let content = "fn add(a: i32, b: i32) -> i32 { a + b }\n";
+        let store = store_for("t.rs", content);
+
+        let lang = store.triples_matching(Some("add"), Some(PRED_LANGUAGE), None);
+        assert_eq!(lang, vec![("add", PRED_LANGUAGE, "rust")]);
+    }
+
+    #[test]
+    fn unknown_term_in_any_position_yields_no_matches() {
+        let content = "fn add(a: i32, b: i32) -> i32 { a + b }\n";
+        let store = store_for("t.rs", content);
+
+        assert!(store
+            .triples_matching(Some("does_not_exist"), None, None)
+            .is_empty());
+        assert!(store
+            .triples_matching(None, Some("does_not_exist"), None)
+            .is_empty());
+        assert!(store
+            .triples_matching(None, None, Some("does_not_exist"))
+            .is_empty());
+    }
+
+    #[test]
+    fn wildcard_subject_and_object_finds_summary_by_predicate() {
+        let content = "/// Adds two numbers.\nfn add(a: i32, b: i32) -> i32 { a + b }\n";
+        let store = store_for("t.rs", content);
+
+        let summaries = store.triples_matching(None, Some(PRED_SUMMARY), None);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].0, "add");
+    }
+}
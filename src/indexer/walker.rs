@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use glob::Pattern;
+use ignore::WalkBuilder;
 use walkdir::WalkDir;
 
 /// Collect all indexable files under `root`.
@@ -9,11 +10,19 @@ use walkdir::WalkDir;
 /// If no include globs are given, files whose extension is in `default_exts` are kept.
 /// Files matching any `exclude` glob are always dropped.
 /// Hidden directories (starting with `.`) are skipped.
+///
+/// When `respect_ignore` is set, directories covered by `.gitignore`, `.ignore`,
+/// and the user's global gitignore (looked up the directory tree, same as `git`
+/// itself) are pruned before the include/exclude glob layer ever sees them —
+/// letting a real repo's `target/`, `node_modules/`, etc. stay out of the index
+/// without hand-listing every one in `default_excludes`. Pass `false` to fall
+/// back to the old behavior of walking everything not hidden or glob-excluded.
 pub fn collect_files(
     root: &Path,
     include: &[String],
     exclude: &[String],
     default_exts: &[String],
+    respect_ignore: bool,
 ) -> Vec<PathBuf> {
     let include_patterns: Vec<Pattern> = include
         .iter()
@@ -24,56 +33,68 @@ pub fn collect_files(
         .filter_map(|g| Pattern::new(g).ok())
         .collect();
 
-    WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| {
-            if e.file_type().is_dir() {
-                let name = e.file_name().to_str().unwrap_or("");
-                if name.starts_with('.') {
-                    return false;
-                }
-                // Prune directories covered by any exclude pattern
+    let keep = |path: &Path| -> bool {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        let rel_str = rel.to_string_lossy();
+
+        // Apply exclude patterns
+        if exclude_patterns.iter().any(|p| p.matches(&rel_str)) {
+            return false;
+        }
+
+        // Apply include patterns or default extension filter
+        if !include_patterns.is_empty() {
+            include_patterns.iter().any(|p| p.matches(&rel_str))
+        } else {
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            default_exts.iter().any(|e| e == ext)
+        }
+    };
+
+    if respect_ignore {
+        let mut builder = WalkBuilder::new(root);
+        builder.hidden(true).git_ignore(true).git_global(true).git_exclude(true).parents(true);
+        let dir_exclude_patterns = exclude_patterns.clone();
+        builder.filter_entry(move |e| {
+            if e.file_type().is_some_and(|t| t.is_dir()) {
                 let rel = e.path().strip_prefix(root).unwrap_or(e.path());
                 let probe = format!("{}/x", rel.to_string_lossy());
-                if exclude_patterns.iter().any(|p| p.matches(&probe)) {
+                if dir_exclude_patterns.iter().any(|p| p.matches(&probe)) {
                     return false;
                 }
             }
             true
-        })
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter_map(|e| {
-            let path = e.path();
-
-            // Get path relative to root for glob matching
-            let rel = path.strip_prefix(root).unwrap_or(path);
-            let rel_str = rel.to_string_lossy();
+        });
 
-            // Apply exclude patterns
-            for pat in &exclude_patterns {
-                if pat.matches(&rel_str) {
-                    return None;
+        builder
+            .build()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+            .filter(|e| keep(e.path()))
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    } else {
+        WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| {
+                if e.file_type().is_dir() {
+                    let name = e.file_name().to_str().unwrap_or("");
+                    if name.starts_with('.') {
+                        return false;
+                    }
+                    // Prune directories covered by any exclude pattern
+                    let rel = e.path().strip_prefix(root).unwrap_or(e.path());
+                    let probe = format!("{}/x", rel.to_string_lossy());
+                    if exclude_patterns.iter().any(|p| p.matches(&probe)) {
+                        return false;
+                    }
                 }
-            }
-
-            // Apply include patterns or default extension filter
-            if !include_patterns.is_empty() {
-                let matched = include_patterns.iter().any(|p| p.matches(&rel_str));
-                if !matched {
-                    return None;
-                }
-            } else {
-                let ext = path
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("");
-                if !default_exts.iter().any(|e| e == ext) {
-                    return None;
-                }
-            }
-
-            Some(path.to_path_buf())
-        })
-        .collect()
+                true
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| keep(e.path()))
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    }
 }
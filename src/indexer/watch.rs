@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use glob::Pattern;
+use notify::{EventKind, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::config::AppConfig;
+use crate::db::cache::EmbeddingCache;
+use crate::db::store::Store;
+use crate::embed;
+use crate::error::{AppError, Result};
+
+use super::parser::{self, IncrementalParser};
+use super::{cache::ChunkCache, index_one_file, watch_marker_path, EMBED_CACHE_TABLE};
+
+/// Per-path incremental parser state, reused across successive edits to the
+/// same file for as long as the watch daemon runs. Populated lazily the
+/// first time a path is seen (or re-seeded after a parse miss); dropped
+/// whenever a file is removed.
+struct IncrementalState {
+    content: String,
+    parser: IncrementalParser,
+}
+
+/// Quiet period a burst of filesystem events must settle for before a
+/// changed file is (re-)indexed, so a file mid-save doesn't trigger several
+/// re-indexes in a row.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// How often the heartbeat file is touched while watching, so that
+/// `refresh()` can tell this daemon is still alive (see `WATCH_MARKER_TTL`).
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Run `maharajah watch`: block the process, watching `target_dir` for
+/// filesystem changes and eagerly keeping the `Store` fresh in the
+/// background, so that `find`/`query` run against an already-up-to-date
+/// index instead of paying their own synchronous refresh.
+pub async fn run(config: &AppConfig, db_path: &Path, target_dir: &Path) -> Result<()> {
+    let store = Store::from_addr(
+        &crate::config::resolve_store_addr(config, db_path),
+        config.db.embedding_dim,
+        &config.db.table_name,
+        false,
+    )
+    .await?;
+
+    let embedder = embed::build(config).await?;
+    let cache = EmbeddingCache::open_or_create(db_path, embedder.dimension(), EMBED_CACHE_TABLE)
+        .await?;
+    let model_id = embed::model_id(config);
+    let query_dir = crate::config::resolve_query_dir(config);
+    let mut chunk_cache = ChunkCache::open_or_create(db_path)?;
+    let mut incremental: HashMap<PathBuf, IncrementalState> = HashMap::new();
+
+    let exclude_patterns: Vec<Pattern> = config
+        .index
+        .default_excludes
+        .iter()
+        .filter_map(|g| Pattern::new(g).ok())
+        .collect();
+
+    spawn_heartbeat(db_path.to_path_buf());
+
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel::<notify::Event>();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            match event.kind {
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
+                    let _ = event_tx.send(event);
+                }
+                _ => {}
+            }
+        }
+    })
+    .map_err(|e| AppError::Other(e.into()))?;
+    watcher
+        .watch(target_dir, RecursiveMode::Recursive)
+        .map_err(|e| AppError::Other(e.into()))?;
+
+    println!("Watching {} for changes (Ctrl-C to stop)...", target_dir.display());
+
+    loop {
+        let Some(first) = event_rx.recv().await else {
+            break;
+        };
+
+        // Debounce: keep coalescing the burst until no event has arrived for
+        // one whole quiet window, instead of reacting to every event.
+        let mut paths: HashSet<PathBuf> = first.paths.into_iter().collect();
+        while let Ok(Some(event)) = tokio::time::timeout(DEBOUNCE_WINDOW, event_rx.recv()).await {
+            paths.extend(event.paths);
+        }
+
+        for path in paths {
+            if !is_watched_file(&path, target_dir, &exclude_patterns, &config.index.default_extensions) {
+                continue;
+            }
+
+            let result = if path.exists() {
+                let precomputed_chunks = reparse_incrementally(
+                    &mut incremental,
+                    &path,
+                    config.index.max_chunk_lines,
+                    config.index.chunk_overlap_lines,
+                    config.index.max_chunk_tokens,
+                );
+
+                index_one_file(
+                    &store,
+                    &*embedder,
+                    &cache,
+                    config.cache.enabled,
+                    &mut chunk_cache,
+                    &model_id,
+                    target_dir,
+                    &path,
+                    false,
+                    config.index.max_chunk_lines,
+                    config.index.chunk_overlap_lines,
+                    config.index.max_chunk_tokens,
+                    config.index.max_embed_tokens,
+                    Some(&query_dir),
+                    precomputed_chunks,
+                )
+                .await
+                .map(|indexed| if indexed { "indexed" } else { "skipped (unchanged)" })
+            } else {
+                incremental.remove(&path);
+                let rel_path = path
+                    .strip_prefix(target_dir)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .into_owned();
+                store.delete_file(&rel_path).await.map(|_| "removed")
+            };
+
+            match result {
+                Ok(outcome) => tracing::info!("watch: {} — {outcome}", path.display()),
+                Err(e) => tracing::error!("watch: failed to update {}: {e}", path.display()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reparse `path` through its cached [`IncrementalParser`] (creating and
+/// seeding one on first sight) and return the resulting chunks, so
+/// `index_one_file` can skip its own full tree-sitter walk. Returns `None`
+/// when the file can't be read or has no registered grammar — callers fall
+/// back to `index_one_file`'s normal full-parse path in that case.
+fn reparse_incrementally(
+    incremental: &mut HashMap<PathBuf, IncrementalState>,
+    path: &Path,
+    max_chunk_lines: usize,
+    overlap_lines: usize,
+    max_chunk_tokens: usize,
+) -> Option<Vec<parser::Chunk>> {
+    let new_content = std::fs::read_to_string(path).ok()?;
+
+    if let Some(state) = incremental.get_mut(path) {
+        let edits = parser::diff_edits(&state.content, &new_content);
+        let (chunks, diff) = state.parser.reparse(&state.content, &new_content, &edits);
+        tracing::debug!(
+            "watch: incremental reparse of {} — {} added, {} removed, {} changed",
+            path.display(),
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        );
+        state.content = new_content;
+        return Some(chunks);
+    }
+
+    let mut inc_parser = IncrementalParser::new(path, max_chunk_lines, overlap_lines, max_chunk_tokens)?;
+    let chunks = inc_parser.parse(&new_content);
+    incremental.insert(path.to_path_buf(), IncrementalState { content: new_content, parser: inc_parser });
+    Some(chunks)
+}
+
+/// Whether `path` falls inside `target_dir`, isn't excluded, and has an
+/// indexable extension: `walker::collect_files`'s hidden-path, exclude and
+/// extension checks for a single path. `.gitignore`/`.ignore` rules are not
+/// consulted.
+fn is_watched_file(
+    path: &Path,
+    target_dir: &Path,
+    exclude_patterns: &[Pattern],
+    default_exts: &[String],
+) -> bool {
+    let Ok(rel) = path.strip_prefix(target_dir) else {
+        return false;
+    };
+    let rel_str = rel.to_string_lossy();
+
+    if rel_str.split('/').any(|part| part.starts_with('.')) {
+        return false;
+    }
+    if exclude_patterns.iter().any(|p| p.matches(&rel_str)) {
+        return false;
+    }
+
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    default_exts.iter().any(|e| e == ext)
+}
+
+/// Periodically touch the heartbeat file so `indexer::refresh()` knows a
+/// watch daemon is alive and defers to it instead of scanning the tree itself.
+fn spawn_heartbeat(db_path: PathBuf) {
+    tokio::spawn(async move {
+        let marker = watch_marker_path(&db_path);
+        loop {
+            if let Err(e) = std::fs::write(&marker, b"") {
+                tracing::warn!("watch: could not write heartbeat file: {e}");
+            }
+            tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+        }
+    });
+}
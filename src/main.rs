@@ -5,16 +5,22 @@ mod embed;
 mod error;
 mod indexer;
 mod rag;
+mod server;
+
+use std::path::PathBuf;
 
 use anyhow::Result;
 use clap::Parser;
 use cli::{Cli, Commands, DbAction};
 use db::store::Store;
+use indexer::graph::{CallGraph, ChunkRef};
 use tracing_subscriber::{fmt, EnvFilter};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let raw_args: Vec<String> = std::env::args().collect();
+    let expanded_args = expand_aliases(raw_args)?;
+    let cli = Cli::parse_from(expanded_args);
 
     let filter = match cli.verbose {
         0 => "warn",
@@ -40,22 +46,38 @@ async fn main() -> Result<()> {
     let project_cfg = project_cfg_path.exists().then_some(project_cfg_path.as_path());
 
     // 5. Load layered config
-    let cfg = config::load(&global_cfg_path, project_cfg)?;
+    let mut cfg = config::load(&global_cfg_path, project_cfg)?;
 
-    // 6. Compute DB path from target dir
+    // 6. Compute DB path from target dir. An explicit --db-addr overrides
+    //    config.db.addr, which in turn overrides the derived on-disk path —
+    //    see `config::resolve_store_addr`.
     let db_path = config::db_path(&target_dir);
+    config::apply_cli_overrides(
+        &mut cfg,
+        cli.ollama_url,
+        cli.embed_model,
+        cli.db_addr,
+        None,
+        None,
+        Vec::new(),
+        None,
+    );
 
     match cli.command {
         Commands::Index(args) => {
             indexer::run(&cfg, &db_path, &target_dir, args).await?;
         }
+        Commands::Query(args) => {
+            rag::pipeline::run(&cfg, &db_path, &target_dir, args).await?;
+        }
         Commands::Find(args) => {
             rag::retriever::find_cmd(&cfg, &db_path, &target_dir, args).await?;
         }
         Commands::Db(args) => {
+            let store_addr = config::resolve_store_addr(&cfg, &db_path);
             match args.action {
                 DbAction::Stats => {
-                    match Store::try_open(&db_path, cfg.db.embedding_dim, &cfg.db.table_name)
+                    match Store::try_from_addr(&store_addr, cfg.db.embedding_dim, &cfg.db.table_name)
                         .await?
                     {
                         None => println!("No index found. Run `index` first."),
@@ -72,7 +94,7 @@ async fn main() -> Result<()> {
                     if !yes {
                         println!("Pass --yes to confirm clearing all indexed data.");
                     } else {
-                        match Store::try_open(&db_path, cfg.db.embedding_dim, &cfg.db.table_name)
+                        match Store::try_from_addr(&store_addr, cfg.db.embedding_dim, &cfg.db.table_name)
                             .await?
                         {
                             None => println!("No index found. Nothing to clear."),
@@ -83,8 +105,76 @@ async fn main() -> Result<()> {
                         }
                     }
                 }
+                DbAction::CacheStats => {
+                    let cache = db::cache::EmbeddingCache::open_or_create(
+                        &db_path,
+                        cfg.db.embedding_dim,
+                        indexer::EMBED_CACHE_TABLE,
+                    )
+                    .await?;
+                    println!("Cached vectors : {}", cache.count().await?);
+                    println!("Max entries    : {}", cfg.cache.max_entries);
+                }
+                DbAction::CachePrune => {
+                    let cache = db::cache::EmbeddingCache::open_or_create(
+                        &db_path,
+                        cfg.db.embedding_dim,
+                        indexer::EMBED_CACHE_TABLE,
+                    )
+                    .await?;
+                    let removed = cache.prune_to(cfg.cache.max_entries).await?;
+                    println!("Pruned {removed} entries (cap: {}).", cfg.cache.max_entries);
+                }
+                DbAction::Graph { symbol } => {
+                    match Store::try_from_addr(&store_addr, cfg.db.embedding_dim, &cfg.db.table_name)
+                        .await?
+                    {
+                        None => println!("No index found. Run `index` first."),
+                        Some(store) => {
+                            let records = store.scan_all().await?;
+                            let refs: Vec<ChunkRef> = records
+                                .iter()
+                                .map(|r| ChunkRef {
+                                    symbol: r.qualified_symbol.clone(),
+                                    file_path: r.file_path.clone(),
+                                    references: r.references.clone(),
+                                })
+                                .collect();
+                            let graph = CallGraph::build(&refs, false);
+                            let callers = graph.callers(&symbol);
+                            let callees = graph.callees(&symbol);
+                            if callers.is_empty() && callees.is_empty() {
+                                println!(
+                                    "No call-graph edges found for `{symbol}` (not indexed, or no resolved references)."
+                                );
+                            } else {
+                                println!("Callers of {symbol}:");
+                                for c in &callers {
+                                    println!("  {c}");
+                                }
+                                println!("Callees of {symbol}:");
+                                for c in &callees {
+                                    println!("  {c}");
+                                }
+                            }
+                        }
+                    }
+                }
             }
         }
+        Commands::Migrate(args) => {
+            if !args.yes {
+                println!("This re-embeds every indexed chunk and replaces the table. Pass --yes to confirm.");
+            } else {
+                db::migrate::run(&cfg, &db_path).await?;
+            }
+        }
+        Commands::Watch => {
+            indexer::watch::run(&cfg, &db_path, &target_dir).await?;
+        }
+        Commands::Server(args) => {
+            server::run_server(args, cfg, db_path, target_dir).await?;
+        }
         Commands::Config => {
             println!("{}", serde_json::to_string_pretty(&cfg)?);
         }
@@ -92,3 +182,131 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Names clap already binds to a `Commands` variant — an alias sharing one
+/// of these is always shadowed rather than overriding it, mirroring how
+/// Cargo treats built-in subcommands as non-overridable.
+const BUILTIN_COMMANDS: &[&str] =
+    &["index", "query", "find", "db", "migrate", "watch", "server", "config"];
+
+/// Global flags that take a value, in both their short and long forms — used
+/// to skip past `--flag value` pairs while scanning for the first positional
+/// argument. Kept in sync with the `global = true` fields on `Cli`.
+const GLOBAL_VALUE_FLAGS: &[&str] = &[
+    "-c",
+    "--config",
+    "--ollama-url",
+    "--embed-model",
+    "--db-addr",
+    "-D",
+    "--dir",
+];
+
+/// Maximum number of alias expansions chained together before giving up —
+/// bounds `a = ["b"]`, `b = ["a"]`-style loops instead of recursing forever.
+const MAX_ALIAS_DEPTH: usize = 8;
+
+/// Index of the first positional (non-flag) argument in `args`, skipping
+/// `args[0]` (the binary name) and any global flags (with their values)
+/// that precede the subcommand name. `None` if there isn't one.
+fn first_positional_index(args: &[String]) -> Option<usize> {
+    let mut i = 1;
+    while i < args.len() {
+        let tok = args[i].as_str();
+        if tok == "--" {
+            return (i + 1 < args.len()).then_some(i + 1);
+        }
+        if tok.starts_with('-') {
+            // `--flag=value` carries its value in the same token.
+            if !tok.contains('=') && GLOBAL_VALUE_FLAGS.contains(&tok) {
+                i += 2;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+        return Some(i);
+    }
+    None
+}
+
+/// Resolve `[alias]` entries from the global/project config before handing
+/// `args` to clap: if the first positional argument matches a configured
+/// alias and isn't shadowed by a built-in subcommand, splice in the alias's
+/// stored argument vector and repeat (so an alias can expand to another
+/// alias) up to `MAX_ALIAS_DEPTH` times.
+///
+/// This re-derives the config/target-dir resolution `main` does after
+/// parsing, since alias lookup has to happen before `Cli::parse_from` can
+/// run — `--config`/`--dir` overrides are honored by scanning the raw args
+/// directly rather than relying on clap.
+fn expand_aliases(mut args: Vec<String>) -> Result<Vec<String>> {
+    let target_dir = extract_flag_value(&args, &["-D", "--dir"])
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().expect("cannot read current directory"));
+    let global_cfg_path =
+        extract_flag_value(&args, &["-c", "--config"]).map_or_else(config::global_config_path, PathBuf::from);
+    config::ensure_global_config(&global_cfg_path)?;
+    let project_cfg_path = target_dir.join("maharajah.toml");
+    let project_cfg = project_cfg_path.exists().then_some(project_cfg_path.as_path());
+    let cfg = config::load(&global_cfg_path, project_cfg)?;
+
+    if cfg.alias.is_empty() {
+        return Ok(args);
+    }
+
+    for _ in 0..MAX_ALIAS_DEPTH {
+        let Some(pos) = first_positional_index(&args) else {
+            break;
+        };
+        if BUILTIN_COMMANDS.contains(&args[pos].as_str()) {
+            break;
+        }
+        let Some(expansion) = cfg.alias.get(&args[pos]) else {
+            break;
+        };
+        args.splice(pos..=pos, expansion.iter().cloned());
+    }
+
+    if let Some(pos) = first_positional_index(&args) {
+        if !BUILTIN_COMMANDS.contains(&args[pos].as_str()) && cfg.alias.contains_key(&args[pos]) {
+            anyhow::bail!(
+                "alias `{}` did not resolve to a built-in command after {MAX_ALIAS_DEPTH} expansions \
+                 (possible alias loop)",
+                args[pos]
+            );
+        }
+    }
+
+    Ok(args)
+}
+
+/// Scan `args` for the value of the first flag in `names` (either
+/// `--flag value` or `--flag=value`), stopping once a positional argument
+/// (i.e. the subcommand) is reached.
+fn extract_flag_value(args: &[String], names: &[&str]) -> Option<String> {
+    let mut i = 1;
+    while i < args.len() {
+        let tok = args[i].as_str();
+        if let Some((flag, value)) = tok.split_once('=') {
+            if names.contains(&flag) {
+                return Some(value.to_string());
+            }
+            i += 1;
+            continue;
+        }
+        if names.contains(&tok) {
+            return args.get(i + 1).cloned();
+        }
+        if tok.starts_with('-') {
+            if GLOBAL_VALUE_FLAGS.contains(&tok) {
+                i += 2;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+        break;
+    }
+    None
+}
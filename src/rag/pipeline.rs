@@ -3,12 +3,13 @@ use std::path::Path;
 use futures::StreamExt;
 use ollama_rs::{Ollama, generation::completion::request::GenerationRequest};
 
-use crate::cli::QueryArgs;
+use crate::cli::{QueryArgs, SearchMode};
 use crate::config::AppConfig;
-use crate::db::store::Store;
-use crate::embed::ollama::OllamaEmbedder;
+use crate::db::store::{SearchFilters, Store};
+use crate::embed;
 use crate::error::{AppError, Result};
 use crate::indexer;
+use crate::rag::retriever::{mmr_rerank, rrf_merge};
 
 pub async fn run(config: &AppConfig, db_path: &Path, target_dir: &Path, args: QueryArgs) -> Result<()> {
     // Auto-refresh changed files before querying
@@ -17,15 +18,52 @@ pub async fn run(config: &AppConfig, db_path: &Path, target_dir: &Path, args: Qu
         println!("[auto-refresh: {refreshed} file(s) updated]");
     }
 
-    // 1. Embed the question
-    let embedder = OllamaEmbedder::new(config.ollama.clone())?;
-    let vector = embedder.embed(&args.question).await?;
+    // 1. Retrieve relevant chunks
+    let store = Store::from_addr(
+        &crate::config::resolve_store_addr(config, db_path),
+        config.db.embedding_dim, &config.db.table_name, false,
+    ).await?
+    .with_ann_params(config.db.ann_nprobes, config.db.ann_refine_factor as u32);
 
-    // 2. Retrieve relevant chunks
-    let store = Store::open_or_create(
-        db_path, config.db.embedding_dim, &config.db.table_name, false,
-    ).await?;
-    let results = store.search(&vector, args.top_k).await?;
+    let filters = SearchFilters {
+        language: args.lang.clone(),
+        path_glob: args.path.clone(),
+        symbol_prefix: args.symbol_prefix.clone(),
+        ..Default::default()
+    };
+
+    let results = match args.mode {
+        SearchMode::Vector => {
+            let embedder = embed::build(config).await?;
+            let vector = embedder.embed_query(&args.question).await?;
+            let fetch_limit = if args.mmr_lambda < 1.0 {
+                args.top_k.saturating_mul(4).max(args.top_k)
+            } else {
+                args.top_k
+            };
+            let candidates = store.search(&vector, fetch_limit, &filters).await?;
+            mmr_rerank(&vector, candidates, args.mmr_lambda, args.top_k)
+        }
+        SearchMode::Lexical => store.full_text_search(&args.question, args.top_k, &filters).await?,
+        SearchMode::Blended => {
+            let embedder = embed::build(config).await?;
+            let vector = embedder.embed_query(&args.question).await?;
+            store.search_blended(&vector, args.top_k, args.alpha, &filters).await?
+        }
+        SearchMode::Hybrid => {
+            let embedder = embed::build(config).await?;
+            let vector = embedder.embed_query(&args.question).await?;
+            let (vector_results, summary_results, lexical_results) = tokio::join!(
+                store.search(&vector, args.top_k, &filters),
+                store.search_by_summary(&vector, args.top_k, &filters),
+                store.full_text_search(&args.question, args.top_k, &filters)
+            );
+            rrf_merge(
+                vec![vector_results?, summary_results?, lexical_results?],
+                args.top_k,
+            )
+        }
+    };
 
     if results.is_empty() {
         println!("No relevant code found for this question.");
@@ -3,11 +3,11 @@ use std::path::Path;
 
 use serde::Serialize;
 
-use crate::cli::{FindArgs, OutputFormat};
+use crate::cli::{FindArgs, OutputFormat, SearchMode};
 use crate::config::AppConfig;
-use crate::db::store::{SearchResult, Store};
-use crate::embed::nomic::NomicEmbedder;
-use crate::error::{AppError, Result};
+use crate::db::store::{SearchFilters, SearchResult, Store};
+use crate::embed;
+use crate::error::Result;
 use crate::indexer;
 
 #[derive(Serialize)]
@@ -34,24 +34,63 @@ pub async fn find_cmd(
         println!("[auto-refresh: {refreshed} file(s) updated]");
     }
 
-    // Load embedder and embed the query in one spawn_blocking call
-    let prompt = args.prompt.clone();
-    let vector = tokio::task::spawn_blocking(move || {
-        NomicEmbedder::load()?.embed_query(&prompt)
-    })
-    .await
-    .map_err(|e| AppError::Other(e.into()))?
-    .map_err(|e| AppError::Embed(e.to_string()))?;
-
-    let store = Store::open_or_create(
-        db_path,
+    let store = Store::from_addr(
+        &crate::config::resolve_store_addr(config, db_path),
         config.db.embedding_dim,
         &config.db.table_name,
         false,
     )
-    .await?;
+    .await?
+    .with_ann_params(config.db.ann_nprobes, config.db.ann_refine_factor as u32);
 
-    let results = store.search(&vector, args.limit).await?;
+    let limit = args.limit.unwrap_or(config.retrieve.result_limit);
+    let min_score = args.min_score.unwrap_or(config.retrieve.min_score);
+    let symbol_boost = args.symbol_boost.unwrap_or(config.retrieve.symbol_boost);
+    let mut exclude_languages = config.retrieve.exclude_languages.clone();
+    exclude_languages.extend(args.exclude_lang.iter().cloned());
+
+    let filters = SearchFilters {
+        language: args.lang.clone(),
+        path_glob: args.path.clone(),
+        symbol_prefix: args.symbol_prefix.clone(),
+        exclude_languages,
+    };
+
+    let results = match args.mode {
+        SearchMode::Vector => {
+            let embedder = embed::build(config).await?;
+            let vector = embedder.embed_query(&args.prompt).await?;
+
+            let fetch_limit = if args.mmr_lambda < 1.0 { limit.saturating_mul(4).max(limit) } else { limit };
+            let candidates = store.search(&vector, fetch_limit, &filters).await?;
+            mmr_rerank(&vector, candidates, args.mmr_lambda, limit)
+        }
+        SearchMode::Lexical => store.full_text_search(&args.prompt, limit, &filters).await?,
+        SearchMode::Hybrid => {
+            let embedder = embed::build(config).await?;
+            let vector = embedder.embed_query(&args.prompt).await?;
+
+            let (vector_results, summary_results, lexical_results) = tokio::join!(
+                store.search(&vector, limit, &filters),
+                store.search_by_summary(&vector, limit, &filters),
+                store.full_text_search(&args.prompt, limit, &filters)
+            );
+            rrf_merge(
+                vec![vector_results?, summary_results?, lexical_results?],
+                limit,
+            )
+        }
+        SearchMode::Blended => {
+            let embedder = embed::build(config).await?;
+            let vector = embedder.embed_query(&args.prompt).await?;
+
+            store.search_blended(&vector, limit, args.alpha, &filters).await?
+        }
+    };
+
+    let higher_is_better = higher_is_better(args.mode);
+    let results = apply_symbol_boost(results, &args.prompt, symbol_boost, higher_is_better);
+    let results = apply_min_score(results, min_score, higher_is_better);
 
     if results.is_empty() {
         println!("No results found.");
@@ -67,7 +106,7 @@ pub async fn find_cmd(
                     format!("  {}", r.symbol)
                 };
                 println!(
-                    "[{}] dist:{:.4}  {}:{}-{}{}",
+                    "[{}] score:{:.4}  {}:{}-{}{}",
                     i + 1,
                     r.score,
                     r.file_path,
@@ -115,21 +154,22 @@ pub async fn find_cmd(
     Ok(())
 }
 
-pub(crate) fn rrf_merge(
-    content_results: Vec<SearchResult>,
-    summary_results: Vec<SearchResult>,
-    limit: usize,
-) -> Vec<SearchResult> {
+/// Reciprocal-rank-fuse any number of independently-ranked result lists
+/// (e.g. content vector, summary vector, full-text) into one, deduping by
+/// `id`. Each list contributes `1/(K + rank)` per appearance; a chunk's
+/// per-list contributions are summed and the pool is re-sorted on that sum.
+/// Input scores are ignored — RRF only looks at rank, so lists with
+/// incomparable scales (cosine distance vs. BM25) combine without
+/// normalization.
+pub(crate) fn rrf_merge(lists: Vec<Vec<SearchResult>>, limit: usize) -> Vec<SearchResult> {
     const K: f32 = 60.0;
     let mut scores: HashMap<String, (SearchResult, f32)> = HashMap::new();
 
-    for (rank, r) in content_results.into_iter().enumerate() {
-        let rrf = 1.0 / (K + (rank + 1) as f32);
-        scores.entry(r.id.clone()).and_modify(|(_, s)| *s += rrf).or_insert((r, rrf));
-    }
-    for (rank, r) in summary_results.into_iter().enumerate() {
-        let rrf = 1.0 / (K + (rank + 1) as f32);
-        scores.entry(r.id.clone()).and_modify(|(_, s)| *s += rrf).or_insert((r, rrf));
+    for list in lists {
+        for (rank, r) in list.into_iter().enumerate() {
+            let rrf = 1.0 / (K + (rank + 1) as f32);
+            scores.entry(r.id.clone()).and_modify(|(_, s)| *s += rrf).or_insert((r, rrf));
+        }
     }
 
     let mut merged: Vec<(SearchResult, f32)> = scores.into_values().collect();
@@ -138,97 +178,128 @@ pub(crate) fn rrf_merge(
     merged.into_iter().map(|(mut r, rrf_score)| { r.score = rrf_score; r }).collect()
 }
 
-pub async fn query_cmd(
-    config: &AppConfig,
-    db_path: &std::path::Path,
-    target_dir: &std::path::Path,
-    args: FindArgs,
-) -> Result<()> {
-    // Auto-refresh changed files before searching
-    let (refreshed, _) = indexer::refresh(config, db_path, target_dir).await?;
-    if refreshed > 0 {
-        println!("[auto-refresh: {refreshed} file(s) updated]");
+/// Cosine similarity between two equal-length embedding vectors.
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
     }
+}
 
-    // Load embedder and embed the query in one spawn_blocking call
-    let prompt = args.prompt.clone();
-    let vector = tokio::task::spawn_blocking(move || {
-        NomicEmbedder::load()?.embed_query(&prompt)
-    })
-    .await
-    .map_err(|e| AppError::Other(e.into()))?
-    .map_err(|e| AppError::Embed(e.to_string()))?;
-
-    let store = Store::open_or_create(
-        db_path,
-        config.db.embedding_dim,
-        &config.db.table_name,
-        false,
-    )
-    .await?;
+/// Rerank `candidates` (already ranked best match first, i.e. by ascending
+/// `_distance` as `Store::search` returns them) by Maximal
+/// Marginal Relevance: greedily pick the candidate maximizing
+/// `lambda * cos(query, d) - (1 - lambda) * max_selected cos(d, selected)`,
+/// stopping at `top_k`. `lambda = 1.0` reproduces plain top-k-by-score
+/// ranking (and skips the O(top_k * candidates) MMR loop entirely).
+/// Candidates missing a stored `vector` (e.g. from a lexical-only search)
+/// contribute 0 similarity in either role, rather than being dropped.
+pub(crate) fn mmr_rerank(
+    query_vector: &[f32],
+    candidates: Vec<SearchResult>,
+    lambda: f32,
+    top_k: usize,
+) -> Vec<SearchResult> {
+    if lambda >= 1.0 || candidates.len() <= 1 {
+        let mut candidates = candidates;
+        candidates.truncate(top_k);
+        return candidates;
+    }
 
-    let content_results = store.search(&vector, args.limit).await?;
-    let summary_results = store.search_by_summary(&vector, args.limit).await?;
-    let results = rrf_merge(content_results, summary_results, args.limit);
+    let relevance: Vec<f32> = candidates
+        .iter()
+        .map(|c| c.vector.as_deref().map(|v| cosine(query_vector, v)).unwrap_or(0.0))
+        .collect();
 
-    if results.is_empty() {
-        println!("No results found.");
-        return Ok(());
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+    let mut selected: Vec<usize> = Vec::new();
+
+    while !remaining.is_empty() && selected.len() < top_k {
+        let (pos, _) = remaining
+            .iter()
+            .enumerate()
+            .map(|(pos, &i)| {
+                let max_sim_to_selected = selected
+                    .iter()
+                    .map(|&j| match (candidates[i].vector.as_deref(), candidates[j].vector.as_deref()) {
+                        (Some(vi), Some(vj)) => cosine(vi, vj),
+                        _ => 0.0,
+                    })
+                    .fold(0.0f32, f32::max);
+                let mmr_score = lambda * relevance[i] - (1.0 - lambda) * max_sim_to_selected;
+                (pos, mmr_score)
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .expect("remaining is non-empty");
+        selected.push(remaining.remove(pos));
     }
 
-    match args.format {
-        OutputFormat::Text => {
-            for (i, r) in results.iter().enumerate() {
-                let symbol_display = if r.symbol.is_empty() {
-                    String::new()
-                } else {
-                    format!("  {}", r.symbol)
-                };
-                println!(
-                    "[{}] rrf:{:.4}  {}:{}-{}{}",
-                    i + 1,
-                    r.score,
-                    r.file_path,
-                    r.start_line,
-                    r.end_line,
-                    symbol_display
-                );
-                if let Some(ref s) = r.summary {
-                    println!("  summary: {}", s);
-                }
-                let preview: String = r
-                    .content
-                    .lines()
-                    .take(3)
-                    .map(|l| format!("  {}", l))
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                println!("{}", preview);
-                println!();
-            }
-        }
-        OutputFormat::Json => {
-            let json_results: Vec<JsonResult> = results
-                .into_iter()
-                .enumerate()
-                .map(|(i, r)| JsonResult {
-                    rank: i + 1,
-                    file_path: r.file_path,
-                    start_line: r.start_line,
-                    end_line: r.end_line,
-                    symbol: r.symbol,
-                    score: r.score,
-                    content: r.content,
-                    summary: r.summary,
-                })
-                .collect();
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&json_results)
-                    .map_err(|e| crate::error::AppError::Other(e.into()))?
-            );
-        }
+    let mut by_index: Vec<Option<SearchResult>> = candidates.into_iter().map(Some).collect();
+    selected
+        .into_iter()
+        .map(|i| by_index[i].take().expect("each index is selected at most once"))
+        .collect()
+}
+
+/// Whether a higher [`SearchResult::score`] means a better match under
+/// `mode`: true for BM25 (`Lexical`) and RRF (`Hybrid`, see [`rrf_merge`])
+/// scores, false for `Vector`/`Blended`, whose score is the raw LanceDB
+/// `_distance` (`Store::search`/`search_by_summary`/`search_blended`) —
+/// lower means closer. Boosting and `min_score` filtering both need to know
+/// this to move scores (and cutoffs) in the right direction.
+fn higher_is_better(mode: SearchMode) -> bool {
+    matches!(mode, SearchMode::Lexical | SearchMode::Hybrid)
+}
+
+/// Add `boost` per query term (whitespace-split, case-insensitive) that
+/// appears as a substring of a result's `symbol`, then re-sort by the
+/// boosted score — so a hit like `fn parse_file` outranks a chunk that only
+/// matches `parse_file` in its content or summary. A `boost` of 0.0 (the
+/// default) leaves scores and ordering untouched. `higher_is_better` (see
+/// that function) controls both the sign of the adjustment and the sort
+/// direction, since a `Vector`/`Blended` score is a distance the boost needs
+/// to shrink, not grow.
+pub(crate) fn apply_symbol_boost(
+    mut results: Vec<SearchResult>,
+    query: &str,
+    boost: f32,
+    higher_is_better: bool,
+) -> Vec<SearchResult> {
+    if boost == 0.0 {
+        return results;
+    }
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    let signed_boost = if higher_is_better { boost } else { -boost };
+    for r in &mut results {
+        let symbol_lower = r.symbol.to_lowercase();
+        let hits = terms.iter().filter(|t| symbol_lower.contains(t.as_str())).count();
+        r.score += signed_boost * hits as f32;
     }
+    if higher_is_better {
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    } else {
+        results.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal));
+    }
+    results
+}
 
-    Ok(())
+/// Apply `config.retrieve.min_score`/`--min-score` as a cutoff on `results`,
+/// mode-aware per [`higher_is_better`]: `0.0` (the default, for either
+/// direction) keeps every result, since a `Vector`/`Blended` distance cutoff
+/// of exactly `0.0` would otherwise drop nearly everything. A nonzero cutoff
+/// keeps `score >= min_score` for higher-is-better modes and `score <=
+/// min_score` (a maximum distance) otherwise.
+fn apply_min_score(results: Vec<SearchResult>, min_score: f32, higher_is_better: bool) -> Vec<SearchResult> {
+    if min_score == 0.0 {
+        return results;
+    }
+    if higher_is_better {
+        results.into_iter().filter(|r| r.score >= min_score).collect()
+    } else {
+        results.into_iter().filter(|r| r.score <= min_score).collect()
+    }
 }
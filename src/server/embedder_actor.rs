@@ -1,38 +1,69 @@
+use std::sync::Arc;
+
 use anyhow::Result;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::oneshot;
 
-use crate::embed::nomic::NomicEmbedder;
+use crate::config::AppConfig;
+use crate::embed::{self, Embedder};
 
-pub type EmbedRequest = (String, oneshot::Sender<Result<Vec<f32>>>);
+/// A batch of queries to embed in one round trip (a single query is just a
+/// batch of one) and the channel to send the matching batch of vectors back
+/// on, in request order.
+pub type EmbedRequest = (Vec<String>, oneshot::Sender<Result<Vec<Vec<f32>>>>);
 
-/// Spawn a dedicated OS thread that owns the `NomicEmbedder`.
-/// Returns a sender that callers use to embed queries.
-/// Each request is `(query_string, oneshot::Sender<Result<Vec<f32>>>)`.
-pub fn spawn_embedder_actor() -> mpsc::Sender<EmbedRequest> {
-    let (tx, mut rx) = mpsc::channel::<EmbedRequest>(32);
+/// Spawn a pool of `pool_size` OS threads, each building its own embedder via
+/// `embed::build(config)` — the same config-driven constructor `indexer::run`
+/// and `indexer::refresh` use, so the server embeds queries with whichever
+/// provider is actually configured rather than a hardcoded one — and pulling
+/// from one shared multi-consumer queue, so concurrent `find`/`query`
+/// requests load-balance across idle embedders instead of serializing behind
+/// a single one.
+///
+/// Each worker drives its embedder on a small current-thread Tokio runtime:
+/// `Embedder`'s methods are async (some providers, like Ollama, make a
+/// network call), but the CPU-bound local providers still benefit from a
+/// dedicated OS thread per embedder rather than sharing the main runtime.
+///
+/// `async_channel`'s receiver is `Clone` and supports both async and
+/// blocking `recv`, so each worker can block on it directly without needing
+/// its own multi-threaded runtime. When every clone of the returned sender
+/// is dropped, `recv_blocking` starts returning `Err` and each worker thread
+/// exits on its own — no separate shutdown signal needed.
+pub fn spawn_embedder_pool(config: AppConfig, pool_size: usize) -> async_channel::Sender<EmbedRequest> {
+    let (tx, rx) = async_channel::bounded::<EmbedRequest>(32);
 
-    std::thread::spawn(move || {
-        let embedder = match NomicEmbedder::load() {
-            Ok(e) => e,
-            Err(err) => {
-                tracing::error!("Embedder failed to load: {err}");
-                return;
-            }
-        };
+    for worker_id in 0..pool_size {
+        let rx = rx.clone();
+        let config = config.clone();
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(rt) => rt,
+                Err(err) => {
+                    tracing::error!("Embedder worker {worker_id} failed to start runtime: {err}");
+                    return;
+                }
+            };
 
-        // Drive the receiver on a single-threaded runtime so we can use async recv
-        // without moving the embedder across threads.
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .build()
-            .expect("embedder actor runtime");
+            let embedder: Arc<dyn Embedder> = match runtime.block_on(embed::build(&config)) {
+                Ok(e) => e,
+                Err(err) => {
+                    tracing::error!("Embedder worker {worker_id} failed to load: {err}");
+                    return;
+                }
+            };
 
-        rt.block_on(async move {
-            while let Some((query, reply_tx)) = rx.recv().await {
-                let result = embedder.embed_query(&query);
+            while let Ok((queries, reply_tx)) = rx.recv_blocking() {
+                let result = runtime.block_on(async {
+                    let mut out = Vec::with_capacity(queries.len());
+                    for q in &queries {
+                        out.push(embedder.embed_query(q).await?);
+                    }
+                    Ok(out)
+                });
                 let _ = reply_tx.send(result);
             }
         });
-    });
+    }
 
     tx
 }
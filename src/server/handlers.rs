@@ -1,9 +1,16 @@
 use actix_web::{HttpResponse, Responder, web};
+use futures::StreamExt;
 use tokio::sync::oneshot;
 
-use crate::db::store::Store;
-use crate::rag::retriever::rrf_merge;
+use crate::db::store::{SearchFilters, SearchResult, Store};
+use crate::rag::retriever::{mmr_rerank, rrf_merge};
 use crate::server::AppState;
+use crate::server::metrics::Timer;
+
+/// How many queries in a `/batch` request are searched concurrently. Bounds
+/// memory/connection pressure on the store for a large batch while still
+/// overlapping I/O across queries.
+const BATCH_CONCURRENCY: usize = 4;
 
 #[derive(serde::Deserialize)]
 pub struct SearchRequest {
@@ -11,6 +18,24 @@ pub struct SearchRequest {
     #[serde(default = "default_limit")]
     pub limit: usize,
     pub min_score: Option<f32>,
+    /// Maximal Marginal Relevance tradeoff for `find_handler` and
+    /// `batch_handler` (`query_handler` ignores it); `None` (the default)
+    /// reproduces plain top-k-by-score ranking, same as `1.0`.
+    pub mmr_lambda: Option<f32>,
+    pub lang: Option<String>,
+    pub path: Option<String>,
+    pub symbol_prefix: Option<String>,
+}
+
+impl SearchRequest {
+    fn filters(&self) -> SearchFilters {
+        SearchFilters {
+            language: self.lang.clone(),
+            path_glob: self.path.clone(),
+            symbol_prefix: self.symbol_prefix.clone(),
+            ..Default::default()
+        }
+    }
 }
 
 fn default_limit() -> usize {
@@ -23,13 +48,19 @@ async fn prepare(
     query: &str,
 ) -> Result<(Vec<f32>, Store), HttpResponse> {
     let (reply_tx, reply_rx) = oneshot::channel();
-    if state.embed_tx.send((query.to_owned(), reply_tx)).await.is_err() {
-        return Err(HttpResponse::InternalServerError().body("Embedder not available"));
-    }
-    let vector = match reply_rx.await {
-        Ok(Ok(v)) => v,
-        Ok(Err(e)) => return Err(HttpResponse::InternalServerError().body(e.to_string())),
-        Err(_) => return Err(HttpResponse::InternalServerError().body("Embedder channel closed")),
+    let vector = {
+        let _timer = Timer::start(state.metrics.embed_latency.clone());
+        if state.embed_tx.send((vec![query.to_owned()], reply_tx)).await.is_err() {
+            return Err(HttpResponse::InternalServerError().body("Embedder not available"));
+        }
+        match reply_rx.await {
+            Ok(Ok(mut vs)) => match vs.pop() {
+                Some(v) => v,
+                None => return Err(HttpResponse::InternalServerError().body("Embedder returned no vector")),
+            },
+            Ok(Err(e)) => return Err(HttpResponse::InternalServerError().body(e.to_string())),
+            Err(_) => return Err(HttpResponse::InternalServerError().body("Embedder channel closed")),
+        }
     };
 
     let store = Store::open_or_create(
@@ -39,7 +70,8 @@ async fn prepare(
         false,
     )
     .await
-    .map_err(|e| HttpResponse::InternalServerError().body(e.to_string()))?;
+    .map_err(|e| HttpResponse::InternalServerError().body(e.to_string()))?
+    .with_ann_params(state.config.db.ann_nprobes, state.config.db.ann_refine_factor as u32);
 
     Ok((vector, store))
 }
@@ -48,19 +80,37 @@ pub async fn find_handler(
     state: web::Data<AppState>,
     body: web::Json<SearchRequest>,
 ) -> impl Responder {
+    state.metrics.requests_total.with_label_values(&["find"]).inc();
+
     let (vector, store) = match prepare(&state, &body.query).await {
         Ok(v) => v,
-        Err(e) => return e,
+        Err(e) => {
+            state.metrics.errors_total.with_label_values(&["find"]).inc();
+            return e;
+        }
+    };
+
+    let lambda = body.mmr_lambda.unwrap_or(1.0);
+    let fetch_limit = if lambda < 1.0 { body.limit.saturating_mul(4).max(body.limit) } else { body.limit };
+
+    let results = {
+        let _timer = Timer::start(state.metrics.search_latency.with_label_values(&["search"]));
+        store.search(&vector, fetch_limit, &body.filters()).await
     };
 
-    match store.search(&vector, body.limit).await {
+    match results {
         Ok(results) => {
+            let results = mmr_rerank(&vector, results, lambda, body.limit);
             let filtered: Vec<_> = results.into_iter()
                 .filter(|r| body.min_score.map_or(true, |t| r.score >= t))
                 .collect();
+            state.metrics.search_result_count.with_label_values(&["search"]).observe(filtered.len() as f64);
             HttpResponse::Ok().json(filtered)
         }
-        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+        Err(e) => {
+            state.metrics.errors_total.with_label_values(&["find"]).inc();
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
     }
 }
 
@@ -68,23 +118,148 @@ pub async fn query_handler(
     state: web::Data<AppState>,
     body: web::Json<SearchRequest>,
 ) -> impl Responder {
+    state.metrics.requests_total.with_label_values(&["query"]).inc();
+
     let (vector, store) = match prepare(&state, &body.query).await {
         Ok(v) => v,
-        Err(e) => return e,
+        Err(e) => {
+            state.metrics.errors_total.with_label_values(&["query"]).inc();
+            return e;
+        }
     };
 
     let limit = body.limit;
-    let (content_res, summary_res) =
-        tokio::join!(store.search(&vector, limit), store.search_by_summary(&vector, limit));
+    let filters = body.filters();
+    let (content_res, summary_res) = {
+        let _content_timer = Timer::start(state.metrics.search_latency.with_label_values(&["search"]));
+        let _summary_timer = Timer::start(state.metrics.search_latency.with_label_values(&["search_by_summary"]));
+        tokio::join!(
+            store.search(&vector, limit, &filters),
+            store.search_by_summary(&vector, limit, &filters)
+        )
+    };
 
     match (content_res, summary_res) {
         (Ok(content), Ok(summary)) => {
-            let merged = rrf_merge(content, summary, limit);
+            state.metrics.search_result_count.with_label_values(&["search"]).observe(content.len() as f64);
+            state.metrics.search_result_count.with_label_values(&["search_by_summary"]).observe(summary.len() as f64);
+            let merged = rrf_merge(vec![content, summary], limit);
+            state.metrics.rrf_merge_output_size.observe(merged.len() as f64);
             let filtered: Vec<_> = merged.into_iter()
                 .filter(|r| body.min_score.map_or(true, |t| r.score >= t))
                 .collect();
             HttpResponse::Ok().json(filtered)
         }
-        (Err(e), _) | (_, Err(e)) => HttpResponse::InternalServerError().body(e.to_string()),
+        (Err(e), _) | (_, Err(e)) => {
+            state.metrics.errors_total.with_label_values(&["query"]).inc();
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
     }
 }
+
+/// Expose registered metrics in Prometheus text exposition format.
+pub async fn metrics_handler(state: web::Data<AppState>) -> impl Responder {
+    match state.metrics.gather() {
+        Ok(body) => HttpResponse::Ok().content_type("text/plain; version=0.0.4").body(body),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct BatchSearchRequest {
+    pub queries: Vec<SearchRequest>,
+}
+
+#[derive(serde::Serialize)]
+pub struct BatchResultItem {
+    pub results: Option<Vec<SearchResult>>,
+    pub error: Option<String>,
+}
+
+/// Search several queries in one request: every query string is embedded in
+/// a single actor round trip via `embed_tx`'s batch channel, the store is
+/// opened once, and the per-query searches run with `BATCH_CONCURRENCY`-wide
+/// parallelism. A failure on one query becomes an `error` entry rather than
+/// failing the whole batch; the output array is aligned to `queries`' order.
+pub async fn batch_handler(
+    state: web::Data<AppState>,
+    body: web::Json<BatchSearchRequest>,
+) -> impl Responder {
+    state.metrics.requests_total.with_label_values(&["batch"]).inc();
+
+    if body.queries.is_empty() {
+        return HttpResponse::Ok().json(Vec::<BatchResultItem>::new());
+    }
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let texts = body.queries.iter().map(|q| q.query.clone()).collect();
+    let vectors = {
+        let _timer = Timer::start(state.metrics.embed_latency.clone());
+        if state.embed_tx.send((texts, reply_tx)).await.is_err() {
+            state.metrics.errors_total.with_label_values(&["batch"]).inc();
+            return HttpResponse::InternalServerError().body("Embedder not available");
+        }
+        match reply_rx.await {
+            Ok(Ok(vs)) => vs,
+            Ok(Err(e)) => {
+                state.metrics.errors_total.with_label_values(&["batch"]).inc();
+                return HttpResponse::InternalServerError().body(e.to_string());
+            }
+            Err(_) => {
+                state.metrics.errors_total.with_label_values(&["batch"]).inc();
+                return HttpResponse::InternalServerError().body("Embedder channel closed");
+            }
+        }
+    };
+
+    let store = match Store::open_or_create(
+        &state.db_path,
+        state.config.db.embedding_dim,
+        &state.config.db.table_name,
+        false,
+    )
+    .await
+    {
+        Ok(s) => s.with_ann_params(state.config.db.ann_nprobes, state.config.db.ann_refine_factor as u32),
+        Err(e) => {
+            state.metrics.errors_total.with_label_values(&["batch"]).inc();
+            return HttpResponse::InternalServerError().body(e.to_string());
+        }
+    };
+
+    let metrics = &state.metrics;
+    let results: Vec<BatchResultItem> = futures::stream::iter(body.queries.iter().zip(vectors))
+        .map(|(req, vector)| {
+            let store = &store;
+            async move {
+                let lambda = req.mmr_lambda.unwrap_or(1.0);
+                let fetch_limit =
+                    if lambda < 1.0 { req.limit.saturating_mul(4).max(req.limit) } else { req.limit };
+
+                let _timer = Timer::start(metrics.search_latency.with_label_values(&["search"]));
+                match store.search(&vector, fetch_limit, &req.filters()).await {
+                    Ok(results) => {
+                        let results = mmr_rerank(&vector, results, lambda, req.limit);
+                        let filtered: Vec<_> = results
+                            .into_iter()
+                            .filter(|r| req.min_score.map_or(true, |t| r.score >= t))
+                            .collect();
+                        metrics
+                            .search_result_count
+                            .with_label_values(&["search"])
+                            .observe(filtered.len() as f64);
+                        BatchResultItem { results: Some(filtered), error: None }
+                    }
+                    Err(e) => {
+                        metrics.errors_total.with_label_values(&["batch"]).inc();
+                        BatchResultItem { results: None, error: Some(e.to_string()) }
+                    }
+                }
+            }
+        })
+        .buffered(BATCH_CONCURRENCY)
+        .collect()
+        .await;
+
+    HttpResponse::Ok().json(results)
+}
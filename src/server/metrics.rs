@@ -0,0 +1,91 @@
+use std::time::Instant;
+
+use prometheus::{Histogram, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+
+/// Prometheus metrics for the server's hot paths, registered once in
+/// [`crate::server::AppState`] and shared across requests.
+pub struct Metrics {
+    registry: Registry,
+    pub embed_latency: Histogram,
+    pub search_latency: HistogramVec,
+    pub search_result_count: HistogramVec,
+    pub rrf_merge_output_size: Histogram,
+    pub requests_total: IntCounterVec,
+    pub errors_total: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> anyhow::Result<Self> {
+        let registry = Registry::new();
+
+        let embed_latency = Histogram::with_opts(HistogramOpts::new(
+            "maharajah_embed_round_trip_seconds",
+            "Latency of an embed-actor round trip (channel send to oneshot receive)",
+        ))?;
+        let search_latency = HistogramVec::new(
+            HistogramOpts::new("maharajah_search_latency_seconds", "Latency of a store search call"),
+            &["kind"],
+        )?;
+        let search_result_count = HistogramVec::new(
+            HistogramOpts::new("maharajah_search_result_count", "Number of results returned by a store search call")
+                .buckets(vec![0.0, 1.0, 5.0, 10.0, 20.0, 50.0, 100.0]),
+            &["kind"],
+        )?;
+        let rrf_merge_output_size = Histogram::with_opts(HistogramOpts::new(
+            "maharajah_rrf_merge_output_size",
+            "Number of results produced by rrf_merge",
+        ))?;
+        let requests_total = IntCounterVec::new(
+            Opts::new("maharajah_requests_total", "Total requests handled, by handler"),
+            &["handler"],
+        )?;
+        let errors_total = IntCounterVec::new(
+            Opts::new("maharajah_errors_total", "Total request errors, by handler"),
+            &["handler"],
+        )?;
+
+        registry.register(Box::new(embed_latency.clone()))?;
+        registry.register(Box::new(search_latency.clone()))?;
+        registry.register(Box::new(search_result_count.clone()))?;
+        registry.register(Box::new(rrf_merge_output_size.clone()))?;
+        registry.register(Box::new(requests_total.clone()))?;
+        registry.register(Box::new(errors_total.clone()))?;
+
+        Ok(Metrics {
+            registry,
+            embed_latency,
+            search_latency,
+            search_result_count,
+            rrf_merge_output_size,
+            requests_total,
+            errors_total,
+        })
+    }
+
+    /// Render all registered metrics in Prometheus text exposition format.
+    pub fn gather(&self) -> anyhow::Result<String> {
+        let mut buf = String::new();
+        TextEncoder::new().encode_utf8(&self.registry.gather(), &mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// Observes elapsed wall-clock time into `histogram` when dropped, so a
+/// single `let _t = Timer::start(histogram);` at the top of an `await`ed
+/// block records its latency regardless of which `?` exit path is taken.
+pub struct Timer {
+    histogram: Histogram,
+    start: Instant,
+}
+
+impl Timer {
+    pub fn start(histogram: Histogram) -> Self {
+        Timer { histogram, start: Instant::now() }
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.histogram.observe(self.start.elapsed().as_secs_f64());
+    }
+}
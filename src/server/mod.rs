@@ -1,21 +1,24 @@
 pub mod embedder_actor;
 pub mod handlers;
+pub mod metrics;
 pub mod watcher;
 
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use actix_web::{App, HttpServer, web};
-use tokio::sync::mpsc;
 
 use crate::cli::ServerArgs;
 use crate::config::AppConfig;
 use embedder_actor::EmbedRequest;
+use metrics::Metrics;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub embed_tx: mpsc::Sender<EmbedRequest>,
+    pub embed_tx: async_channel::Sender<EmbedRequest>,
     pub db_path: PathBuf,
     pub config: AppConfig,
+    pub metrics: Arc<Metrics>,
 }
 
 pub async fn run_server(
@@ -27,18 +30,22 @@ pub async fn run_server(
     let bind_addr = format!("{}:{}", args.host, args.port);
     tracing::info!("Starting server on {bind_addr}");
 
-    tracing::info!("Loading embedder model...");
-    let embed_tx = embedder_actor::spawn_embedder_actor();
+    let pool_size = crate::config::resolve_embed_pool_size(&config);
+    tracing::info!("Loading embedder model ({pool_size} worker(s))...");
+    let embed_tx = embedder_actor::spawn_embedder_pool(config.clone(), pool_size);
 
     let _watcher = watcher::spawn_watcher(target_dir, db_path.clone(), config.clone())?;
 
-    let state = AppState { embed_tx, db_path, config };
+    let metrics = Arc::new(Metrics::new()?);
+    let state = AppState { embed_tx, db_path, config, metrics };
 
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(state.clone()))
             .route("/find", web::post().to(handlers::find_handler))
             .route("/query", web::post().to(handlers::query_handler))
+            .route("/batch", web::post().to(handlers::batch_handler))
+            .route("/metrics", web::get().to(handlers::metrics_handler))
     })
     .bind(&bind_addr)?
     .run()